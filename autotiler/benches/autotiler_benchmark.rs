@@ -1,6 +1,8 @@
 use std::time::{Duration, Instant};
+use rand::seq::SliceRandom;
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
-use autotiler::{grid, matrix, tile}; // Replace with the actual crate and module names
+use autotiler::{bitboard, grid, lookup, matrix, pixel_matrix, point, random, rect, sparse, strip, tile}; // Replace with the actual crate and module names
+use autotiler::tile_grid::TileGrid;
 
 
 fn benchmark_stripping_in_place(c: &mut Criterion) {
@@ -44,7 +46,7 @@ fn benchmark_stripping_matrix(c: &mut Criterion) {
             b.iter_custom(|iters| {
                 let mut total_duration = Duration::ZERO;
                 for _i in 0..iters {
-                    let mut test_matrix = matrix::generate_random_matrix(&tile_set, size, size);
+                    let test_matrix = matrix::generate_random_matrix(&tile_set, size, size);
 
                     // benchmarking only the actual stripping
                     let start = Instant::now();
@@ -62,6 +64,233 @@ fn benchmark_stripping_matrix(c: &mut Criterion) {
     group.finish()
 }
 
+fn benchmark_stripping_bitboard(c: &mut Criterion) {
+    let tile_set = tile::minimal_3x3_tile_set();
+
+    let mut group = c.benchmark_group("bitboard stripping invalids");
+
+    for size in [8, 32, 128, 512, 2048].iter() {
+        group.bench_with_input(BenchmarkId::from_parameter(size), size, |b, &size| {
+            b.iter_custom(|iters| {
+                let mut total_duration = Duration::ZERO;
+                for _i in 0..iters {
+                    let mut test_board = bitboard::BitBoard::new(rect::Rect::new(0, 0, size, size));
+                    random::fill_random(&mut test_board, &tile_set);
+
+                    // benchmarking only the actual stripping
+                    let start = Instant::now();
+
+                    let stripped_board = test_board.strip_invalid();
+                    black_box(stripped_board);
+
+                    total_duration += start.elapsed();
+                }
+                total_duration
+            })
+        });
+    }
+
+    group.finish()
+}
+
+fn benchmark_stripping_dirty_region(c: &mut Criterion) {
+    let tile_set = tile::minimal_3x3_tile_set();
+    let every_tile: Vec<tile::Tile3x3> = (0..512).map(tile::Tile3x3::from_bits).collect();
+
+    let mut group = c.benchmark_group("stripping a brush stroke");
+
+    for size in [128, 512, 2048].iter() {
+        let (valid, _) = grid::grid_strip_invalid_fixed_point(&grid::generate_test_grid(&tile_set, *size, *size));
+
+        group.bench_with_input(BenchmarkId::from_parameter(size), size, |b, &size| {
+            // a stroke of 16 arbitrary tiles across the middle of a valid grid
+            let mid = size as i32 / 2;
+            let stroke: Vec<point::Point> = (0..16).map(|i| point::Point { x: mid - 8 + i, y: mid }).collect();
+
+            b.iter_custom(|iters| {
+                let mut rng = random::seeded_rng(iters);
+                let mut total_duration = Duration::ZERO;
+                for _i in 0..iters {
+                    let mut test_grid = valid.clone();
+                    for pt in &stroke {
+                        test_grid.set_pt(pt, *every_tile.choose(&mut rng).unwrap());
+                    }
+
+                    // benchmarking only the re-stripping of the stroke
+                    let start = Instant::now();
+
+                    let changed = test_grid.strip_invalid_points(&stroke);
+                    black_box(changed);
+
+                    total_duration += start.elapsed();
+                }
+                total_duration
+            })
+        });
+    }
+
+    group.finish()
+}
+
+fn benchmark_solving_lookup(c: &mut Criterion) {
+    let tile_set = tile::minimal_3x3_tile_set();
+    let lookup = lookup::TileLookup::new(&tile_set);
+
+    let mut group = c.benchmark_group("solving with lookup table");
+
+    for size in [8, 32, 128, 512, 2048].iter() {
+        group.bench_with_input(BenchmarkId::new("grid", size), size, |b, &size| {
+            b.iter_custom(|iters| {
+                let mut total_duration = Duration::ZERO;
+                for _i in 0..iters {
+                    let test_grid = grid::generate_test_grid(&tile_set, size, size);
+
+                    // benchmarking only the actual solving
+                    let start = Instant::now();
+
+                    let solved_grid = grid::grid_solve(&test_grid, &lookup);
+                    black_box(solved_grid);
+
+                    total_duration += start.elapsed();
+                }
+                total_duration
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("matrix", size), size, |b, &size| {
+            b.iter_custom(|iters| {
+                let mut total_duration = Duration::ZERO;
+                for _i in 0..iters {
+                    let test_matrix = matrix::generate_random_matrix(&tile_set, size, size);
+
+                    // benchmarking only the actual solving
+                    let start = Instant::now();
+
+                    let solved_matrix = test_matrix.solve(&lookup);
+                    black_box(solved_matrix);
+
+                    total_duration += start.elapsed();
+                }
+                total_duration
+            })
+        });
+    }
+
+    group.finish()
+}
+
+fn bench_generic_strip<G: TileGrid>(b: &mut criterion::Bencher, tile_set: &[tile::Tile3x3], empty: impl Fn() -> G) {
+    b.iter_custom(|iters| {
+        let mut total_duration = Duration::ZERO;
+        for _i in 0..iters {
+            let mut test_grid = empty();
+            random::fill_random(&mut test_grid, tile_set);
+
+            // benchmarking only the actual stripping
+            let start = Instant::now();
+
+            let stripped_grid = strip::strip_invalid(&test_grid);
+            black_box(stripped_grid);
+
+            total_duration += start.elapsed();
+        }
+        total_duration
+    })
+}
+
+fn benchmark_stripping_layouts(c: &mut Criterion) {
+    let tile_set = tile::minimal_3x3_tile_set();
+
+    let mut group = c.benchmark_group("generic stripping across layouts");
+
+    for size in [8, 32, 128, 512].iter() {
+        let bounds = rect::Rect::new(0, 0, *size, *size);
+
+        group.bench_with_input(BenchmarkId::new("rows", size), size, |b, &size| {
+            bench_generic_strip(b, &tile_set, || vec![vec![tile::Tile3x3::EMPTY; size as usize]; size as usize])
+        });
+
+        group.bench_with_input(BenchmarkId::new("rect vec", size), size, |b, _| {
+            bench_generic_strip(b, &tile_set, || grid::RectVec::new(bounds.clone()))
+        });
+
+        group.bench_with_input(BenchmarkId::new("matrix", size), size, |b, _| {
+            bench_generic_strip(b, &tile_set, || matrix::Matrix::new(bounds.clone()))
+        });
+
+        group.bench_with_input(BenchmarkId::new("sparse", size), size, |b, _| {
+            bench_generic_strip(b, &tile_set, || sparse::SparseGrid::new(bounds.clone()))
+        });
+    }
+
+    group.finish()
+}
+
+fn benchmark_stripping_pixel_layouts(c: &mut Criterion) {
+    let tile_set = tile::minimal_3x3_tile_set();
+
+    let mut group = c.benchmark_group("tile-major against pixel-major stripping");
+
+    for size in [8, 32, 128, 512].iter() {
+        group.bench_with_input(BenchmarkId::new("tile-major", size), size, |b, &size| {
+            b.iter_custom(|iters| {
+                let mut total_duration = Duration::ZERO;
+                for _i in 0..iters {
+                    let test_matrix = matrix::generate_random_matrix(&tile_set, size, size);
+
+                    // benchmarking only the actual stripping
+                    let start = Instant::now();
+
+                    let stripped_matrix = test_matrix.strip_invalid();
+                    black_box(stripped_matrix);
+
+                    total_duration += start.elapsed();
+                }
+                total_duration
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("pixel-major", size), size, |b, &size| {
+            b.iter_custom(|iters| {
+                let mut total_duration = Duration::ZERO;
+                for _i in 0..iters {
+                    let test_matrix = matrix::generate_random_matrix(&tile_set, size, size);
+                    let test_pixels = pixel_matrix::PixelMatrix::from(&test_matrix);
+
+                    // benchmarking only the actual stripping
+                    let start = Instant::now();
+
+                    let stripped_pixels = test_pixels.strip_invalid();
+                    black_box(stripped_pixels);
+
+                    total_duration += start.elapsed();
+                }
+                total_duration
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("swizzle", size), size, |b, &size| {
+            b.iter_custom(|iters| {
+                let mut total_duration = Duration::ZERO;
+                for _i in 0..iters {
+                    let test_matrix = matrix::generate_random_matrix(&tile_set, size, size);
+
+                    // benchmarking the round trip between the layouts
+                    let start = Instant::now();
+
+                    let pixels = pixel_matrix::PixelMatrix::from(&test_matrix);
+                    black_box(matrix::Matrix::from(&pixels));
+
+                    total_duration += start.elapsed();
+                }
+                total_duration
+            })
+        });
+    }
+
+    group.finish()
+}
+
 
-criterion_group!(benches, benchmark_stripping_in_place, benchmark_stripping_matrix);
+criterion_group!(benches, benchmark_stripping_in_place, benchmark_stripping_matrix, benchmark_stripping_bitboard, benchmark_stripping_dirty_region, benchmark_solving_lookup, benchmark_stripping_layouts, benchmark_stripping_pixel_layouts);
 criterion_main!(benches);
\ No newline at end of file
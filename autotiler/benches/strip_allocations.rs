@@ -0,0 +1,102 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
+use autotiler::{bitboard, grid, matrix, parallel, random, rect, strip, tile};
+use autotiler::tile_grid::TileGrid;
+
+/// counts every allocation, so the benchmark can show that warm stripping doesn't allocate
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// strips a grid over and over, once allocating a new grid per pass and once ping-ponging
+/// between two buffers, and fails if a warm ping-pong pass allocates
+fn bench_passes<G: strip::StripInto + Clone>(c: &mut Criterion, name: &str, empty: impl Fn(i32) -> G, strip_alloc: impl Fn(&G) -> G) {
+    let tile_set = tile::minimal_3x3_tile_set();
+
+    let mut group = c.benchmark_group(format!("{name} stripping allocations"));
+
+    for size in [32, 128, 512].iter() {
+        let mut test_grid = empty(*size);
+        random::fill_random(&mut test_grid, &tile_set);
+
+        group.bench_with_input(BenchmarkId::new("allocating", size), size, |b, _| {
+            b.iter_custom(|iters| {
+                let mut grid = test_grid.clone();
+
+                let start = Instant::now();
+                for _i in 0..iters {
+                    grid = strip_alloc(&grid);
+                }
+                black_box(&grid);
+
+                start.elapsed()
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("double buffered", size), size, |b, _| {
+            b.iter_custom(|iters| {
+                let mut grid = test_grid.clone();
+                let mut context = strip::StripContext::default();
+
+                // warm up, the first pass allocates the scratch grid
+                context.strip(&mut grid);
+
+                let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+                let start = Instant::now();
+                for _i in 0..iters {
+                    context.strip(&mut grid);
+                }
+                let total_duration: Duration = start.elapsed();
+
+                let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+                assert_eq!(allocations, 0, "{iters} warm passes made {allocations} allocations");
+                black_box(&grid);
+
+                total_duration
+            })
+        });
+    }
+
+    group.finish()
+}
+
+fn benchmark_strip_allocations(c: &mut Criterion) {
+    let serial = parallel::ParallelConfig::serial();
+
+    bench_passes(c, "grid", |size| grid::RectVec::new(rect::Rect::new(0, 0, size, size)), |grid| {
+        grid::grid_strip_invalid_with(grid, &serial)
+    });
+
+    bench_passes(c, "matrix", |size| matrix::Matrix::new(rect::Rect::new(0, 0, size, size)), |matrix| {
+        matrix.strip_invalid_with(&serial)
+    });
+
+    bench_passes(c, "bitboard", |size| bitboard::BitBoard::new(rect::Rect::new(0, 0, size, size)), |board| {
+        board.strip_invalid()
+    });
+}
+
+
+criterion_group!(benches, benchmark_strip_allocations);
+criterion_main!(benches);
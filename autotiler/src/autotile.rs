@@ -1,41 +1,42 @@
 use crate::tile::*;
 use crate::point::Point;
 use crate::rect::Rect;
+use crate::lookup::{self, occupancy_mask, solve_mask, TileLookup};
 
 
-pub fn auto_tile(tile_grid: &mut Vec<Vec<Tile3x3>>) {
-    // solve_tile_grid(&tile_grid)
+/// replaces every tile in the grid with its solved counterpart from the tile set
+pub fn auto_tile(tile_set: &[Tile3x3], tile_grid: &mut Vec<Vec<Tile3x3>>) {
+    *tile_grid = solve_tile_grid(tile_set, tile_grid)
 }
 
-pub fn solve_tile_grid(tile_grid: &[Vec<Tile3x3>]) -> Vec<Vec<Tile3x3>> {
-    let mut solved_grid = Vec::new();
-
-    let bounds = Rect::new(
-        0,
-        0,
-        tile_grid.first().expect("grid to have a row").len() as i32,
-        tile_grid.len() as i32
-    );
-
-    for (y, row) in tile_grid.iter().enumerate() {
-        let mut solved_row = Vec::new();
-
-        for (x, tile) in row.iter().enumerate() {
-            solved_row.push(solve_tile(tile_grid, &bounds, &tile, Point { x: x as i32, y: y as i32 }));
-        }
+/// solves every tile through a lookup table built from the tile set, tiles missing from the set
+/// keep their solved bits
+pub fn solve_tile_grid(tile_set: &[Tile3x3], tile_grid: &[Vec<Tile3x3>]) -> Vec<Vec<Tile3x3>> {
+    let mut solved: Vec<Vec<Tile3x3>> = tile_grid.iter().map(|row| vec![Tile3x3::EMPTY; row.len()]).collect();
+    lookup::solve_into(tile_grid, &TileLookup::new(tile_set), &mut solved);
+    solved
+}
 
-        solved_grid.push(solved_row);
+fn is_occupied(tile_grid: &[Vec<Tile3x3>], bounds: &Rect, pos: &Point) -> bool {
+    if !bounds.contains(pos) {
+        return false;
     }
 
-    solved_grid
+    tile_grid[pos.y as usize][pos.x as usize].get(C_IDX)
 }
 
+/// picks the tile from the tile set which joins the tile at `pos` with all of its occupied
+/// neighbours. unoccupied tiles solve to an empty tile.
+pub fn solve_tile(tile_set: &[Tile3x3], tile_grid: &[Vec<Tile3x3>], bounds: &Rect, tile: &Tile3x3, pos: Point) -> Tile3x3 {
+    if !tile.get(C_IDX) {
+        return Tile3x3::default();
+    }
 
-#[allow(dead_code, unused_variables)]
-fn solve_tile(tile_grid: &[Vec<Tile3x3>], bounds: &Rect, tile: &Tile3x3, pos: Point) -> Tile3x3 {
-    let tile = tile.clone();
-
+    let mask = occupancy_mask(&pos, |pt| is_occupied(tile_grid, bounds, pt));
+    let solved = solve_mask(mask);
 
-    tile
+    tile_set.iter()
+        .find(|candidate| **candidate == solved)
+        .copied()
+        .unwrap_or(solved)
 }
-
@@ -0,0 +1,210 @@
+use crate::grid::RectVec;
+use crate::parallel::ParallelConfig;
+use crate::point::Point;
+use crate::rect::Rect;
+use crate::strip::StripInto;
+use crate::tile::{Tile3x3, C_IDX, E_IDX, N_IDX, NE_IDX, NW_IDX, S_IDX, SE_IDX, SW_IDX, W_IDX};
+use crate::tile_grid::TileGrid;
+use crate::validate::{self, Issue};
+
+/// tiles stored as nine bit-planes, one per tile bit. every row of a plane is packed into `u64`
+/// words, tile x of a row is bit `x % 64` of word `x / 64`, and the bits past the end of a row
+/// are always clear. stripping works on whole words, so 64 tiles are checked at once.
+#[derive(Clone)]
+pub struct BitBoard {
+    planes: [Vec<u64>; 9],
+    words_per_row: usize,
+    pub bounds: Rect,
+}
+
+impl BitBoard {
+    pub fn new(bounds: Rect) -> Self {
+        let words_per_row = (bounds.w.max(0) as usize).div_ceil(64);
+        let len = words_per_row * bounds.h.max(0) as usize;
+
+        Self {
+            planes: std::array::from_fn(|_| vec![0; len]),
+            words_per_row,
+            bounds,
+        }
+    }
+
+    /// the word and the bit inside of it which hold the tile at `pt`
+    fn idx(&self, pt: &Point) -> Option<(usize, u64)> {
+        if !self.bounds.contains(pt) {
+            return None;
+        }
+
+        let (x, y) = ((pt.x - self.bounds.x) as usize, (pt.y - self.bounds.y) as usize);
+        Some((y * self.words_per_row + x / 64, 1 << (x % 64)))
+    }
+
+    pub fn get_tile(&self, pt: &Point) -> Option<Tile3x3> {
+        let (word, mask) = self.idx(pt)?;
+        let mut tile = Tile3x3::EMPTY;
+
+        for (bit, plane) in self.planes.iter().enumerate() {
+            tile.set(bit, plane[word] & mask != 0);
+        }
+
+        Some(tile)
+    }
+
+    pub fn set_tile(&mut self, pt: &Point, value: &Tile3x3) {
+        let Some((word, mask)) = self.idx(pt) else {
+            return;
+        };
+
+        for (bit, plane) in self.planes.iter_mut().enumerate() {
+            if value.get(bit) {
+                plane[word] |= mask;
+            } else {
+                plane[word] &= !mask;
+            }
+        }
+    }
+
+    /// the bits of word `w` which hold tiles, the rest of the word is past the end of the row
+    fn inside(&self, w: usize) -> u64 {
+        match self.bounds.w as usize - w * 64 {
+            remaining if remaining >= 64 => !0,
+            remaining => (1 << remaining) - 1,
+        }
+    }
+
+    /// a word of a plane, where every tile outside of the board is set, so the missing
+    /// neighbours of tiles on the border never strip anything
+    fn word(&self, bit: usize, y: isize, w: isize) -> u64 {
+        if y < 0 || y >= self.bounds.h as isize || w < 0 || w >= self.words_per_row as isize {
+            return !0;
+        }
+
+        let (y, w) = (y as usize, w as usize);
+        self.planes[bit][y * self.words_per_row + w] | !self.inside(w)
+    }
+
+    /// the bits of the tiles `dx` columns and `dy` rows away from the tiles of word `w` in row `y`
+    fn neighbours(&self, bit: usize, y: isize, w: isize, dx: isize, dy: isize) -> u64 {
+        let y = y + dy;
+
+        match dx {
+            -1 => self.word(bit, y, w) << 1 | self.word(bit, y, w - 1) >> 63,
+            1 => self.word(bit, y, w) >> 1 | self.word(bit, y, w + 1) << 63,
+            _ => self.word(bit, y, w),
+        }
+    }
+
+    /// strips into `out` instead of a new board, reshaping it to the bounds of this board. once
+    /// `out` has held a board this size, stripping doesn't allocate.
+    pub fn strip_invalid_into(&self, out: &mut BitBoard) {
+        out.bounds.clone_from(&self.bounds);
+        out.words_per_row = self.words_per_row;
+        for (plane, source) in out.planes.iter_mut().zip(&self.planes) {
+            plane.resize(source.len(), 0);
+        }
+
+        for y in 0..self.bounds.h as isize {
+            for w in 0..self.words_per_row as isize {
+                let at = |bit: usize, dx: isize, dy: isize| self.neighbours(bit, y, w, dx, dy);
+                let tile = |bit: usize| at(bit, 0, 0);
+
+                // clear out invalid edges
+                let n = tile(N_IDX) & at(C_IDX, 0, -1) & at(S_IDX, 0, -1);
+                let west = tile(W_IDX) & at(C_IDX, -1, 0) & at(E_IDX, -1, 0);
+                let e = tile(E_IDX) & at(C_IDX, 1, 0) & at(W_IDX, 1, 0);
+                let s = tile(S_IDX) & at(C_IDX, 0, 1) & at(N_IDX, 0, 1);
+
+                // a corner needs the diagonal neighbour, both orthogonal neighbours and its own edges
+                let nw = tile(NW_IDX) & at(SE_IDX, -1, -1)
+                    & at(C_IDX, 0, -1) & at(SW_IDX, 0, -1)
+                    & at(C_IDX, -1, 0) & at(NE_IDX, -1, 0)
+                    & n & west;
+                let ne = tile(NE_IDX) & at(SW_IDX, 1, -1)
+                    & at(C_IDX, 0, -1) & at(SE_IDX, 0, -1)
+                    & at(C_IDX, 1, 0) & at(NW_IDX, 1, 0)
+                    & n & e;
+                let sw = tile(SW_IDX) & at(NE_IDX, -1, 1)
+                    & at(C_IDX, 0, 1) & at(NW_IDX, 0, 1)
+                    & at(C_IDX, -1, 0) & at(SE_IDX, -1, 0)
+                    & s & west;
+                let se = tile(SE_IDX) & at(NW_IDX, 1, 1)
+                    & at(C_IDX, 0, 1) & at(NE_IDX, 0, 1)
+                    & at(C_IDX, 1, 0) & at(SW_IDX, 1, 0)
+                    & s & e;
+
+                // tiles with a clear centre are cleared entirely
+                let centre = tile(C_IDX) & self.inside(w as usize);
+                let idx = y as usize * self.words_per_row + w as usize;
+
+                for (bit, value) in [nw, n, ne, west, centre, e, sw, s, se].into_iter().enumerate() {
+                    out.planes[bit][idx] = value & centre;
+                }
+            }
+        }
+    }
+
+    /// lists every inconsistent tile and the rule it breaks, without repairing anything
+    pub fn validate(&self, tile_set: &[Tile3x3]) -> Vec<Issue> {
+        validate::validate(self, tile_set)
+    }
+}
+
+impl TileGrid for BitBoard {
+    fn bounds(&self) -> Rect {
+        self.bounds.clone()
+    }
+
+    fn tile_at(&self, pt: &Point) -> Option<Tile3x3> {
+        self.get_tile(pt)
+    }
+
+    fn put_tile(&mut self, pt: &Point, tile: Tile3x3) {
+        self.set_tile(pt, &tile)
+    }
+
+    fn empty_like(&self) -> Self {
+        BitBoard::new(self.bounds.clone())
+    }
+
+    fn is_occupied(&self, pt: &Point) -> bool {
+        self.idx(pt).is_some_and(|(word, mask)| self.planes[C_IDX][word] & mask != 0)
+    }
+
+    /// the same result as `grid_strip_invalid`, computed a word of 64 tiles at a time
+    fn strip_invalid(&self) -> Self {
+        let mut stripped = BitBoard::new(self.bounds.clone());
+        self.strip_invalid_into(&mut stripped);
+        stripped
+    }
+}
+
+/// always strips on the calling thread, a pass is cheap enough that threads don't pay off
+impl StripInto for BitBoard {
+    fn strip_into(&self, out: &mut Self, _config: &ParallelConfig) {
+        self.strip_invalid_into(out)
+    }
+}
+
+impl From<&RectVec> for BitBoard {
+    fn from(grid: &RectVec) -> Self {
+        let mut board = BitBoard::new(grid.bounds.clone());
+
+        for (pos, tile) in grid.iter_enumerate() {
+            board.set_tile(&pos, tile);
+        }
+
+        board
+    }
+}
+
+impl From<&BitBoard> for RectVec {
+    fn from(board: &BitBoard) -> Self {
+        let mut grid = RectVec::new(board.bounds.clone());
+
+        for (pos, tile) in board.iter_tiles() {
+            grid.set_pt(&pos, tile);
+        }
+
+        grid
+    }
+}
@@ -0,0 +1,302 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use image::{GrayImage, Luma};
+use rand::Rng;
+use crate::grid::RectVec;
+use crate::lookup::{occupancy_mask, solve_mask};
+use crate::matrix::Matrix;
+use crate::paint::path_points;
+use crate::point::Point;
+use crate::rect::Rect;
+use crate::tile::C_IDX;
+use crate::tile_grid::TileGrid;
+
+/// a mask which doesn't hold exactly `width * height` cells
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaskSizeError {
+    pub width: u32,
+    pub height: u32,
+    pub len: usize,
+}
+
+impl Display for MaskSizeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let cells = self.width as u64 * self.height as u64;
+        write!(f, "a {}x{} mask needs {} cells, found {}", self.width, self.height, cells, self.len)
+    }
+}
+
+impl Error for MaskSizeError {}
+
+/// a layer of occupied cells, row by row. it's turned into valid, fully joined tiles by
+/// solving every cell against its neighbours, rather than by stripping random tiles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occupancy {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<bool>,
+}
+
+/// the number of cells in a layer, multiplied in `usize` so large layers don't wrap around
+fn cell_count(width: u32, height: u32) -> usize {
+    (width as usize).checked_mul(height as usize).expect("layer has more cells than fit in memory")
+}
+
+impl Occupancy {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![false; cell_count(width, height)],
+        }
+    }
+
+    /// wraps a row by row mask of `width * height` cells
+    pub fn from_mask(width: u32, height: u32, data: Vec<bool>) -> Result<Self, MaskSizeError> {
+        if data.len() as u64 != width as u64 * height as u64 {
+            return Err(MaskSizeError { width, height, len: data.len() });
+        }
+
+        Ok(Self {
+            width,
+            height,
+            data,
+        })
+    }
+
+    /// every pixel at or above `threshold` is occupied
+    pub fn from_gray_image(img: &GrayImage, threshold: u8) -> Self {
+        Self {
+            width: img.width(),
+            height: img.height(),
+            data: img.pixels().map(|Luma([value])| *value >= threshold).collect(),
+        }
+    }
+
+    /// projects tiles back onto their centre pixels, relative to the grid's origin
+    pub fn from_grid<G: TileGrid>(grid: &G) -> Self {
+        let bounds = grid.bounds();
+        let mut occupancy = Occupancy::new(bounds.w as u32, bounds.h as u32);
+
+        for (pt, tile) in grid.iter_tiles() {
+            occupancy.set(&Point { x: pt.x - bounds.x, y: pt.y - bounds.y }, tile.get(C_IDX));
+        }
+
+        occupancy
+    }
+
+    /// occupied cells are white, empty cells are black
+    pub fn to_gray_image(&self) -> GrayImage {
+        GrayImage::from_fn(self.width, self.height, |x, y| {
+            Luma([self.get(&Point { x: x as i32, y: y as i32 }) as u8 * 255])
+        })
+    }
+
+    pub fn bounds(&self) -> Rect {
+        Rect::new(0, 0, self.width as i32, self.height as i32)
+    }
+
+    fn idx(&self, pt: &Point) -> Option<usize> {
+        if self.bounds().contains(pt) {
+            Some(pt.y as usize * self.width as usize + pt.x as usize)
+        } else {
+            None
+        }
+    }
+
+    /// cells outside of the layer are unoccupied
+    pub fn get(&self, pt: &Point) -> bool {
+        self.idx(pt).is_some_and(|idx| self.data[idx])
+    }
+
+    pub fn set(&mut self, pt: &Point, value: bool) {
+        if let Some(idx) = self.idx(pt) {
+            self.data[idx] = value
+        }
+    }
+
+    /// the number of occupied cells
+    pub fn count(&self) -> usize {
+        self.data.iter().filter(|occupied| **occupied).count()
+    }
+
+    /// writes the solved tile of every cell into the grid, offset by the grid's origin
+    pub fn fill_tiles<G: TileGrid>(&self, grid: &mut G) {
+        let origin = grid.bounds();
+
+        for pt in self.bounds().points() {
+            if !self.get(&pt) {
+                continue;
+            }
+
+            let tile = solve_mask(occupancy_mask(&pt, |neighbour| self.get(neighbour)));
+            grid.put_tile(&Point { x: origin.x + pt.x, y: origin.y + pt.y }, tile);
+        }
+    }
+
+    pub fn to_grid(&self) -> RectVec {
+        let mut grid = RectVec::new(self.bounds());
+        self.fill_tiles(&mut grid);
+        grid
+    }
+
+    pub fn to_matrix(&self) -> Matrix {
+        let mut matrix = Matrix::new(self.bounds());
+        self.fill_tiles(&mut matrix);
+        matrix
+    }
+}
+
+/// tiles a row by row mask of `width * height` cells
+pub fn grid_from_mask(mask: &[bool], width: u32, height: u32) -> Result<RectVec, MaskSizeError> {
+    Ok(Occupancy::from_mask(width, height, mask.to_vec())?.to_grid())
+}
+
+/// tiles a row by row mask of `width * height` cells
+pub fn matrix_from_mask(mask: &[bool], width: u32, height: u32) -> Result<Matrix, MaskSizeError> {
+    Ok(Occupancy::from_mask(width, height, mask.to_vec())?.to_matrix())
+}
+
+/// tiles every pixel at or above `threshold`
+pub fn grid_from_gray_image(img: &GrayImage, threshold: u8) -> RectVec {
+    Occupancy::from_gray_image(img, threshold).to_grid()
+}
+
+/// tiles every pixel at or above `threshold`
+pub fn matrix_from_gray_image(img: &GrayImage, threshold: u8) -> Matrix {
+    Occupancy::from_gray_image(img, threshold).to_matrix()
+}
+
+/// the row by row occupancy of a grid, the reverse of `grid_from_mask`
+pub fn mask_from_grid<G: TileGrid>(grid: &G) -> Vec<bool> {
+    Occupancy::from_grid(grid).data
+}
+
+/// clamps a density or coverage into `0.0..=1.0`, where NaN counts as nothing
+fn fraction(value: f64) -> f64 {
+    if value.is_nan() {
+        0.0
+    } else {
+        value.clamp(0.0, 1.0)
+    }
+}
+
+/// every cell is occupied with a chance of `density`, which is clamped into `0.0..=1.0`. a NaN
+/// density occupies nothing.
+pub fn noise<R: Rng + ?Sized>(width: u32, height: u32, density: f64, rng: &mut R) -> Occupancy {
+    let mut occupancy = Occupancy::new(width, height);
+    let density = fraction(density);
+
+    for occupied in occupancy.data.iter_mut() {
+        *occupied = rng.gen_bool(density);
+    }
+
+    occupancy
+}
+
+/// smooths noise of `density` into caves with a cellular automaton. an occupied cell survives
+/// with four or more occupied neighbours, an empty cell fills with five or more. cells outside
+/// of the layer count as occupied, so the caves are closed off at the edges.
+pub fn cellular_caves<R: Rng + ?Sized>(width: u32, height: u32, density: f64, iterations: u32, rng: &mut R) -> Occupancy {
+    let mut occupancy = noise(width, height, density, rng);
+
+    for _ in 0..iterations {
+        let mut next = occupancy.clone();
+
+        for pt in occupancy.bounds().points() {
+            let neighbours = pt.neighbourhood().iter()
+                .filter(|neighbour| **neighbour != pt)
+                .filter(|neighbour| !occupancy.bounds().contains(neighbour) || occupancy.get(neighbour))
+                .count();
+
+            let occupied = if occupancy.get(&pt) { neighbours >= 4 } else { neighbours >= 5 };
+            next.set(&pt, occupied);
+        }
+
+        occupancy = next;
+    }
+
+    occupancy
+}
+
+/// places up to `rooms` non-overlapping rectangular rooms with sides between `min_size` and
+/// `max_size`, and joins each room to the previous one with a corridor
+pub fn rooms_and_corridors<R: Rng + ?Sized>(width: u32, height: u32, rooms: u32, min_size: u32, max_size: u32, rng: &mut R) -> Occupancy {
+    let mut occupancy = Occupancy::new(width, height);
+    let mut placed: Vec<Rect> = Vec::new();
+
+    let max_size = max_size.min(width).min(height);
+    let min_size = min_size.clamp(1, max_size.max(1));
+
+    // give up on a room after a few attempts, the layer might simply be full
+    for _ in 0..u64::from(rooms) * 8 {
+        if placed.len() as u32 == rooms || max_size == 0 {
+            break;
+        }
+
+        let w = rng.gen_range(min_size..=max_size) as i32;
+        let h = rng.gen_range(min_size..=max_size) as i32;
+        let x = rng.gen_range(0..=width as i32 - w);
+        let y = rng.gen_range(0..=height as i32 - h);
+        let room = Rect::new(x, y, w, h);
+
+        let overlaps = placed.iter().any(|other| {
+            room.x <= other.right && other.x <= room.right && room.y <= other.bottom && other.y <= room.bottom
+        });
+        if overlaps {
+            continue;
+        }
+
+        for pt in room.points() {
+            occupancy.set(&pt, true);
+        }
+
+        if let Some(previous) = placed.last() {
+            let from = Point { x: previous.x + previous.w / 2, y: previous.y + previous.h / 2 };
+            let to = Point { x: room.x + room.w / 2, y: room.y + room.h / 2 };
+
+            for pt in path_points(from, to) {
+                occupancy.set(&pt, true);
+            }
+        }
+
+        placed.push(room);
+    }
+
+    occupancy
+}
+
+/// walks randomly from the centre of the layer, occupying every visited cell, until `coverage`
+/// of the layer is occupied
+pub fn drunkard_walk<R: Rng + ?Sized>(width: u32, height: u32, coverage: f64, rng: &mut R) -> Occupancy {
+    let mut occupancy = Occupancy::new(width, height);
+    if width == 0 || height == 0 {
+        return occupancy;
+    }
+
+    let target = (width as f64 * height as f64 * fraction(coverage)).ceil() as usize;
+    let bounds = occupancy.bounds();
+
+    let mut pt = Point { x: width as i32 / 2, y: height as i32 / 2 };
+    let mut occupied = 0;
+
+    while occupied < target {
+        if !occupancy.get(&pt) {
+            occupancy.set(&pt, true);
+            occupied += 1;
+        }
+
+        let next = match rng.gen_range(0..4) {
+            0 => pt.north(),
+            1 => pt.west(),
+            2 => pt.east(),
+            _ => pt.south(),
+        };
+
+        if bounds.contains(&next) {
+            pt = next;
+        }
+    }
+
+    occupancy
+}
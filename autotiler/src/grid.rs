@@ -1,7 +1,13 @@
-use crate::tile::{Tile3x3, C_IDX, E_IDX, N_IDX, NE_IDX, NW_IDX, S_IDX, SE_IDX, SW_IDX, W_IDX};
-use rand::prelude::*;
+use rand::Rng;
+use crate::tile::Tile3x3;
 use crate::point::Point;
 use crate::rect::Rect;
+use crate::random;
+use crate::strip::{self, StripInto, StripStats};
+use crate::lookup::{occupancy_mask, TileLookup};
+use crate::parallel::ParallelConfig;
+use crate::tile_grid::TileGrid;
+use crate::validate::{self, Issue};
 
 
 #[derive(Clone)]
@@ -22,7 +28,7 @@ impl RectVec {
 
     pub fn idx(&self, pt: &Point) -> Option<usize> {
         if self.bounds.contains(pt) {
-            Some((pt.y * self.bounds.w + pt.x) as usize)
+            Some(((pt.y - self.bounds.y) * self.bounds.w + pt.x - self.bounds.x) as usize)
         } else {
             None
         }
@@ -41,103 +47,137 @@ impl RectVec {
 
     pub fn iter_enumerate(&self) -> impl Iterator<Item=(Point, &Tile3x3)> {
         self.data.iter().enumerate().map(|(index, tile)| {
-            let x = index as i32 % self.bounds.w;
-            let y = index as i32 / self.bounds.w;
+            let x = self.bounds.x + index as i32 % self.bounds.w;
+            let y = self.bounds.y + index as i32 / self.bounds.w;
             (Point { x, y }, tile)
         })
     }
-}
 
+    /// lists every inconsistent tile and the rule it breaks, without repairing anything
+    pub fn validate(&self, tile_set: &[Tile3x3]) -> Vec<Issue> {
+        validate::validate_with(self, tile_set, &ParallelConfig::global())
+    }
 
-fn random_tile_from_tile_set(tile_set: &Vec<Tile3x3>) -> Tile3x3 {
-    let mut rng = thread_rng();
-    tile_set.choose(&mut rng).cloned().unwrap()
-}
+    /// calls `f` on every tile, in bands of rows spread across threads for large grids
+    fn for_each_tile_mut(&mut self, config: &ParallelConfig, f: impl Fn(&Point, &mut Tile3x3) + Sync) {
+        let bounds = self.bounds.clone();
+        let (w, h) = (bounds.w as usize, bounds.h as usize);
 
-pub fn generate_test_grid(tile_set: &Vec<Tile3x3>, width: u32, height: u32) -> RectVec {
-    let mut grid = RectVec::new(Rect::new(0, 0, width as i32, height as i32));
+        let f = |band_idx: usize, rows: usize, band: &mut [Tile3x3]| {
+            for (index, tile) in band.iter_mut().enumerate() {
+                let x = bounds.x + (index % w) as i32;
+                let y = bounds.y + (band_idx * rows + index / w) as i32;
+                f(&Point { x, y }, tile);
+            }
+        };
 
-    for y in 0..height {
-        for x in 0..width {
-            grid.set_pt(
-                &Point { x: x as i32, y: y as i32 },
-                random_tile_from_tile_set(tile_set),
-            )
+        if config.is_serial(w * h) {
+            f(0, h, &mut self.data);
+            return;
         }
-    }
 
-    return grid;
+        let rows = config.chunk_rows(h);
+        config.for_each(self.data.chunks_mut((w * rows).max(1)).enumerate().collect(), |(band_idx, band)| {
+            f(band_idx, rows, band)
+        });
+    }
 }
 
-pub fn grid_strip_invalid(tile_grid: &RectVec) -> RectVec {
-    let mut stripped = RectVec::new(tile_grid.bounds.clone());
-
-    for (pos, tile) in tile_grid.iter_enumerate() {
+impl TileGrid for RectVec {
+    fn bounds(&self) -> Rect {
+        self.bounds.clone()
+    }
 
-        let mut tile = tile.clone();
+    fn tile_at(&self, pt: &Point) -> Option<Tile3x3> {
+        self.get_pt(pt).copied()
+    }
 
-        if tile.get(C_IDX) == false {
-            continue;
-        }
+    fn put_tile(&mut self, pt: &Point, tile: Tile3x3) {
+        self.set_pt(pt, tile)
+    }
 
-        // check diagonal neighbour for contiguous fill cases
-        if tile.get(NW_IDX) {
-            if let Some(neighbour) = tile_grid.get_pt(&pos.north_west()) {
-                tile.set(NW_IDX, neighbour.get(SE_IDX));
-            }
-        }
+    fn empty_like(&self) -> Self {
+        RectVec::new(self.bounds.clone())
+    }
 
-        if tile.get(NE_IDX) {
-            if let Some(neighbour) = tile_grid.get_pt(&pos.north_east()) {
-                tile.set(NE_IDX, neighbour.get(SW_IDX));
-            }
-        }
+    fn iter_tiles(&self) -> impl Iterator<Item=(Point, Tile3x3)> + '_ {
+        self.iter_enumerate().map(|(pt, tile)| (pt, *tile))
+    }
 
-        if tile.get(SW_IDX) {
-            if let Some(neighbour) = tile_grid.get_pt(&pos.south_west()) {
-                tile.set(SW_IDX, neighbour.get(NE_IDX));
-            }
-        }
+    fn strip_invalid(&self) -> Self {
+        grid_strip_invalid(self)
+    }
 
-        if tile.get(SE_IDX) {
-            if let Some(neighbour) = tile_grid.get_pt(&pos.south_east()) {
-                tile.set(SE_IDX, neighbour.get(NW_IDX));
-            }
-        }
+    fn solve(&self, lookup: &TileLookup) -> Self {
+        grid_solve(self, lookup)
+    }
+}
 
-        // clear out invalid pixels
-        if let Some(neighbour) = tile_grid.get_pt(&pos.north()) {
-            tile.set(N_IDX, tile.get(N_IDX) & neighbour.get(C_IDX) & neighbour.get(S_IDX) & tile.get(C_IDX));
 
-            tile.set(NW_IDX, tile.get(NW_IDX) & neighbour.get(C_IDX) & neighbour.get(SW_IDX) & tile.get(C_IDX));
-            tile.set(NE_IDX, tile.get(NE_IDX) & neighbour.get(C_IDX) & neighbour.get(SE_IDX) & tile.get(C_IDX));
-        }
+pub fn generate_test_grid(tile_set: &[Tile3x3], width: u32, height: u32) -> RectVec {
+    let mut grid = RectVec::new(Rect::new(0, 0, width as i32, height as i32));
+    random::fill_random(&mut grid, tile_set);
+    grid
+}
 
-        if let Some(neighbour) = tile_grid.get_pt(&pos.west()) {
-            tile.set(W_IDX, tile.get(W_IDX) & neighbour.get(C_IDX) & neighbour.get(E_IDX) & tile.get(C_IDX));
+pub fn generate_test_grid_with_rng<R: Rng + ?Sized>(tile_set: &[Tile3x3], width: u32, height: u32, rng: &mut R) -> RectVec {
+    let mut grid = RectVec::new(Rect::new(0, 0, width as i32, height as i32));
+    random::fill_random_with(&mut grid, tile_set, rng);
+    grid
+}
 
-            tile.set(NW_IDX, tile.get(NW_IDX) & neighbour.get(C_IDX) & neighbour.get(NE_IDX) & tile.get(C_IDX));
-            tile.set(SW_IDX, tile.get(SW_IDX) & neighbour.get(C_IDX) & neighbour.get(SE_IDX) & tile.get(C_IDX));
-        }
+/// the same seed always generates the same grid, and the same tiles as `generate_random_matrix_seeded`
+pub fn generate_test_grid_seeded(tile_set: &[Tile3x3], width: u32, height: u32, seed: u64) -> RectVec {
+    generate_test_grid_with_rng(tile_set, width, height, &mut random::seeded_rng(seed))
+}
 
-        if let Some(neighbour) = tile_grid.get_pt(&pos.east()) {
-            tile.set(E_IDX, tile.get(E_IDX) & neighbour.get(C_IDX) & neighbour.get(W_IDX) & tile.get(C_IDX));
+/// a single pass of `strip::strip_tile` over every tile
+pub fn grid_strip_invalid(tile_grid: &RectVec) -> RectVec {
+    grid_strip_invalid_with(tile_grid, &ParallelConfig::global())
+}
 
-            tile.set(NE_IDX, tile.get(NE_IDX) & neighbour.get(C_IDX) & neighbour.get(NW_IDX) & tile.get(C_IDX));
-            tile.set(SE_IDX, tile.get(SE_IDX) & neighbour.get(C_IDX) & neighbour.get(SW_IDX) & tile.get(C_IDX));
-        }
+pub fn grid_strip_invalid_with(tile_grid: &RectVec, config: &ParallelConfig) -> RectVec {
+    let mut stripped = tile_grid.empty_like();
+    grid_strip_invalid_into(tile_grid, &mut stripped, config);
+    stripped
+}
 
-        if let Some(neighbour) = tile_grid.get_pt(&pos.south()) {
-            tile.set(S_IDX, tile.get(S_IDX) & neighbour.get(C_IDX) & neighbour.get(N_IDX) & tile.get(C_IDX));
+/// strips into `out` instead of a new grid, reshaping it to the bounds of `tile_grid`. once
+/// `out` has held a grid this size, serial stripping doesn't allocate.
+pub fn grid_strip_invalid_into(tile_grid: &RectVec, out: &mut RectVec, config: &ParallelConfig) {
+    out.bounds.clone_from(&tile_grid.bounds);
+    out.data.resize(tile_grid.data.len(), Tile3x3::EMPTY);
 
-            tile.set(SW_IDX, tile.get(SW_IDX) & neighbour.get(C_IDX) & neighbour.get(NW_IDX) & tile.get(C_IDX));
-            tile.set(SE_IDX, tile.get(SE_IDX) & neighbour.get(C_IDX) & neighbour.get(NE_IDX) & tile.get(C_IDX));
-        }
+    out.for_each_tile_mut(config, |pos, tile| {
+        *tile = strip::strip_tile(tile_grid, pos, tile_grid.get_pt(pos).unwrap());
+    });
+}
 
-        stripped.set_pt(&pos, tile);
+impl StripInto for RectVec {
+    fn strip_into(&self, out: &mut Self, config: &ParallelConfig) {
+        grid_strip_invalid_into(self, out, config)
     }
+}
 
-    stripped
+/// repeats `grid_strip_invalid` until a pass changes nothing
+pub fn grid_strip_invalid_fixed_point(tile_grid: &RectVec) -> (RectVec, StripStats) {
+    strip::strip_invalid_fixed_point(tile_grid)
 }
 
+/// table driven solve, every occupied tile is replaced by the tile which joins it with all of
+/// its occupied neighbours
+pub fn grid_solve(tile_grid: &RectVec, lookup: &TileLookup) -> RectVec {
+    grid_solve_with(tile_grid, lookup, &ParallelConfig::global())
+}
+
+pub fn grid_solve_with(tile_grid: &RectVec, lookup: &TileLookup, config: &ParallelConfig) -> RectVec {
+    let mut solved = tile_grid.empty_like();
 
+    solved.for_each_tile_mut(config, |pos, tile| {
+        if tile_grid.is_occupied(pos) {
+            *tile = lookup.tile(occupancy_mask(pos, |pt| tile_grid.is_occupied(pt)));
+        }
+    });
+
+    solved
+}
@@ -4,3 +4,17 @@ pub mod autotile;
 pub mod point;
 pub mod rect;
 pub mod matrix;
+pub mod paint;
+pub mod lookup;
+pub mod loader;
+pub mod symmetry;
+pub mod strip;
+pub mod world;
+pub mod sparse;
+pub mod tile_grid;
+pub mod random;
+pub mod generate;
+pub mod validate;
+pub mod pixel_matrix;
+pub mod parallel;
+pub mod bitboard;
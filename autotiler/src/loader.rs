@@ -0,0 +1,157 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+use image::{DynamicImage, ImageError, Rgba};
+use image::io::Reader as ImageReader;
+use crate::tile::Tile3x3;
+
+/// describes where the tiles sit in a tile set image
+#[derive(Debug, Clone, Copy)]
+pub struct TileSetLayout {
+    /// width and height of a single tile in px
+    pub tile_size: u32,
+    pub columns: u32,
+    pub rows: u32,
+    /// px between the edge of the image and the first tile
+    pub margin: u32,
+    /// px between two neighbouring tiles
+    pub spacing: u32,
+    /// decides whether a sampled pixel sets the bit it was sampled for
+    pub is_set: fn(&Rgba<u8>) -> bool,
+}
+
+impl TileSetLayout {
+    /// the layout of `img/3x3-minimal.png`
+    pub fn minimal_3x3() -> Self {
+        Self {
+            tile_size: 64,
+            columns: 12,
+            rows: 4,
+            margin: 0,
+            spacing: 0,
+            is_set: is_not_white,
+        }
+    }
+}
+
+impl Default for TileSetLayout {
+    fn default() -> Self {
+        Self::minimal_3x3()
+    }
+}
+
+/// if the chunk has a pixel with white, it's not set
+pub fn is_not_white(sample: &Rgba<u8>) -> bool {
+    !(sample.0[0] == 255 && sample.0[1] == 255 && sample.0[2] == 255)
+}
+
+#[derive(Debug)]
+pub enum TileSetError {
+    /// the image file couldn't be opened
+    MissingFile { path: PathBuf, source: io::Error },
+    /// the image data couldn't be decoded
+    Decode(ImageError),
+    /// the image isn't 8 bit rgba
+    NotRgba(image::ColorType),
+    /// the layout samples a pixel outside of the image
+    SampleOutOfRange { x: u32, y: u32, width: u32, height: u32 },
+    /// the layout places a tile further out than any image can reach
+    LayoutOverflow { row: u32, col: u32 },
+}
+
+impl Display for TileSetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TileSetError::MissingFile { path, source } => {
+                write!(f, "couldn't open tile set '{}': {}", path.display(), source)
+            }
+            TileSetError::Decode(err) => write!(f, "couldn't decode tile set: {}", err),
+            TileSetError::NotRgba(color_type) => write!(f, "tile set must be rgba8, found {:?}", color_type),
+            TileSetError::SampleOutOfRange { x, y, width, height } => {
+                write!(f, "sample at ({}, {}) is outside of the {}x{} tile set", x, y, width, height)
+            }
+            TileSetError::LayoutOverflow { row, col } => {
+                write!(f, "tile at row {}, column {} is past the largest pixel coordinate", row, col)
+            }
+        }
+    }
+}
+
+impl Error for TileSetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TileSetError::MissingFile { source, .. } => Some(source),
+            TileSetError::Decode(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ImageError> for TileSetError {
+    fn from(err: ImageError) -> Self {
+        TileSetError::Decode(err)
+    }
+}
+
+pub fn load_tile_set(path: impl AsRef<Path>, layout: &TileSetLayout) -> Result<Vec<Tile3x3>, TileSetError> {
+    let path = path.as_ref();
+
+    let img = ImageReader::open(path).map_err(|source| TileSetError::MissingFile {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    tile_set_from_image(&img.decode()?, layout)
+}
+
+pub fn load_tile_set_from_bytes(bytes: &[u8], layout: &TileSetLayout) -> Result<Vec<Tile3x3>, TileSetError> {
+    tile_set_from_image(&image::load_from_memory(bytes)?, layout)
+}
+
+/// the px offset of tile `idx` along one axis, `None` if it doesn't fit into a u32
+fn tile_offset(layout: &TileSetLayout, idx: u32) -> Option<u32> {
+    idx.checked_mul(layout.tile_size.checked_add(layout.spacing)?)?.checked_add(layout.margin)
+}
+
+/// reads the tiles row by row, sampling the centre of each third of a tile
+pub fn tile_set_from_image(img: &DynamicImage, layout: &TileSetLayout) -> Result<Vec<Tile3x3>, TileSetError> {
+    let rgba = img.as_rgba8().ok_or(TileSetError::NotRgba(img.color()))?;
+
+    // grown as tiles are read, an oversized layout fails on its first sample outside of the image
+    let mut tiles = Vec::new();
+
+    let chunk_size_px = layout.tile_size / 3;
+    let chunk_center_px = chunk_size_px / 2;
+
+    for row in 0..layout.rows {
+        for col in 0..layout.columns {
+            let overflow = || TileSetError::LayoutOverflow { row, col };
+            let tile_y = tile_offset(layout, row).ok_or_else(overflow)?;
+            let tile_x = tile_offset(layout, col).ok_or_else(overflow)?;
+
+            let mut tile = Tile3x3::EMPTY;
+
+            // read tile by sampling the center of each chunk
+            for chunk_y in 0..3 {
+                for chunk_x in 0..3 {
+                    let x = tile_x.checked_add(chunk_x * chunk_size_px + chunk_center_px).ok_or_else(overflow)?;
+                    let y = tile_y.checked_add(chunk_y * chunk_size_px + chunk_center_px).ok_or_else(overflow)?;
+
+                    let sample = rgba.get_pixel_checked(x, y).ok_or(TileSetError::SampleOutOfRange {
+                        x,
+                        y,
+                        width: rgba.width(),
+                        height: rgba.height(),
+                    })?;
+
+                    tile.set_pt(chunk_x as u8, chunk_y as u8, (layout.is_set)(sample));
+                }
+            }
+
+            tiles.push(tile);
+        }
+    }
+
+    Ok(tiles)
+}
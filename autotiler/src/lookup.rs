@@ -0,0 +1,110 @@
+use crate::point::Point;
+use crate::tile_grid::TileGrid;
+use crate::tile::{Tile3x3, C_IDX, E_IDX, N_IDX, NE_IDX, NW_IDX, S_IDX, SE_IDX, SW_IDX, W_IDX};
+
+/// the number of possible 3x3 occupancy masks
+pub const MASK_COUNT: usize = 1 << 9;
+
+/// packs the occupancy of the 3x3 block around `pos` into a mask with the same bit layout as a tile
+#[inline]
+pub fn occupancy_mask(pos: &Point, occupied: impl Fn(&Point) -> bool) -> u16 {
+    let mut mask = 0;
+    for (i, pt) in pos.neighbourhood().iter().enumerate() {
+        mask |= (occupied(pt) as u16) << i;
+    }
+    mask
+}
+
+/// the fully joined tile for an occupancy mask. an unoccupied centre solves to an empty tile,
+/// and a corner only joins when both of the edges next to it are joined as well.
+pub const fn solve_mask(mask: u16) -> Tile3x3 {
+    let occupancy = Tile3x3::from_bits(mask);
+
+    if !occupancy.get(C_IDX) {
+        return Tile3x3::EMPTY;
+    }
+
+    let n = occupancy.get(N_IDX);
+    let w = occupancy.get(W_IDX);
+    let e = occupancy.get(E_IDX);
+    let s = occupancy.get(S_IDX);
+
+    occupancy.edges()
+        .with(C_IDX, true)
+        .with(NW_IDX, n && w && occupancy.get(NW_IDX))
+        .with(NE_IDX, n && e && occupancy.get(NE_IDX))
+        .with(SW_IDX, s && w && occupancy.get(SW_IDX))
+        .with(SE_IDX, s && e && occupancy.get(SE_IDX))
+}
+
+/// maps every 3x3 occupancy mask straight to the matching tile of a tile set, so solving a tile
+/// is a single table lookup instead of a scan over the set.
+///
+/// masks whose solved tile isn't part of the tile set map to [`TileLookup::MISSING`]. solving
+/// such a mask falls back to the solved bits themselves, exactly like `autotile::solve_tile`.
+#[derive(Clone)]
+pub struct TileLookup {
+    indices: [u8; MASK_COUNT],
+    tiles: [Tile3x3; MASK_COUNT],
+}
+
+impl TileLookup {
+    /// index stored for masks which don't solve to a tile in the set
+    pub const MISSING: u8 = u8::MAX;
+
+    pub fn new(tile_set: &[Tile3x3]) -> Self {
+        assert!(tile_set.len() < Self::MISSING as usize, "tile set too large for a lookup table");
+
+        let mut indices = [Self::MISSING; MASK_COUNT];
+        let mut tiles = [Tile3x3::EMPTY; MASK_COUNT];
+
+        for mask in 0..MASK_COUNT {
+            let solved = solve_mask(mask as u16);
+
+            tiles[mask] = solved;
+            if let Some(idx) = tile_set.iter().position(|tile| *tile == solved) {
+                indices[mask] = idx as u8;
+            }
+        }
+
+        Self {
+            indices,
+            tiles,
+        }
+    }
+
+    /// the index into the tile set for a mask, if the set contains its solved tile
+    #[inline]
+    pub fn index(&self, mask: u16) -> Option<usize> {
+        match self.indices[(mask & Tile3x3::MASK) as usize] {
+            Self::MISSING => None,
+            idx => Some(idx as usize),
+        }
+    }
+
+    /// the solved tile for a mask
+    #[inline]
+    pub fn tile(&self, mask: u16) -> Tile3x3 {
+        self.tiles[(mask & Tile3x3::MASK) as usize]
+    }
+}
+
+/// table driven solve, every occupied tile is replaced by the tile which joins it with all of
+/// its occupied neighbours
+pub fn solve<G: TileGrid>(grid: &G, lookup: &TileLookup) -> G {
+    let mut solved = grid.empty_like();
+    solve_into(grid, lookup, &mut solved);
+    solved
+}
+
+/// writes the solved tile of every occupied tile of `grid` into `out`, which should start out
+/// empty. `grid` is only read, so it can be a grid which can't make an empty copy of itself.
+pub fn solve_into<G: TileGrid + ?Sized>(grid: &G, lookup: &TileLookup, out: &mut impl TileGrid) {
+    for (pos, tile) in grid.iter_tiles() {
+        if !tile.get(C_IDX) {
+            continue;
+        }
+
+        out.put_tile(&pos, lookup.tile(occupancy_mask(&pos, |pt| grid.is_occupied(pt))));
+    }
+}
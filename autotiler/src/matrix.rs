@@ -1,11 +1,19 @@
-use std::cmp::{max, min};
-use std::thread;
-use rand::prelude::SliceRandom;
-use rand::thread_rng;
+use rand::Rng;
+use crate::grid::RectVec;
 use crate::point::Point;
 use crate::rect::Rect;
-use crate::tile::{C_IDX, E_IDX, N_IDX, NE_IDX, NW_IDX, S_IDX, SE_IDX, SW_IDX, Tile3x3, W_IDX};
-
+use crate::random;
+use crate::strip::StripInto;
+use crate::lookup::{occupancy_mask, TileLookup};
+use crate::parallel::ParallelConfig;
+use crate::tile_grid::TileGrid;
+use crate::validate::{self, Issue};
+use crate::tile::{Tile3x3, C_IDX, E_IDX, N_IDX, NE_IDX, NW_IDX, S_IDX, SE_IDX, SW_IDX, W_IDX};
+
+/// tiles stored tile-major: the 9 pixels of a tile are contiguous, in the same order as the bits
+/// of a `Tile3x3`, and tiles follow each other row by row. `px_bounds` is `tile_bounds` scaled by
+/// 3, pixel (x, y) is bit `(y % 3) * 3 + x % 3` of tile (x / 3, y / 3). `PixelMatrix` holds the
+/// same pixels as a row-major image.
 #[derive(Clone)]
 pub struct Matrix {
     pub data: Vec<bool>,
@@ -17,7 +25,7 @@ pub type MatrixTile = [bool];
 
 impl Matrix {
     pub fn new(bounds: Rect) -> Self {
-        let px_bounds = Rect::new(bounds.x, bounds.y, bounds.w * 3, bounds.h * 3);
+        let px_bounds = Rect::new(bounds.x * 3, bounds.y * 3, bounds.w * 3, bounds.h * 3);
         let data = vec![false; (px_bounds.w * px_bounds.h) as usize];
         Self {
             data,
@@ -28,19 +36,19 @@ impl Matrix {
 
     pub fn idx_tile(&self, pt: &Point) -> Option<usize> {
         if self.tile_bounds.contains(pt) {
-            let idx = (pt.y * self.px_bounds.w * 3 + pt.x * 9) as usize;
+            let idx = (((pt.y - self.tile_bounds.y) * self.tile_bounds.w + pt.x - self.tile_bounds.x) * 9) as usize;
             Some(idx)
         } else {
             None
         }
     }
 
+    /// the index of the pixel in `data`, inside of the tile which covers it
     pub fn idx(&self, pt: &Point) -> Option<usize> {
-        if self.px_bounds.contains(pt) {
-            Some((pt.y * self.px_bounds.w + pt.x) as usize)
-        } else {
-            None
-        }
+        let tile_pt = Point { x: pt.x.div_euclid(3), y: pt.y.div_euclid(3) };
+        let bit = pt.y.rem_euclid(3) * 3 + pt.x.rem_euclid(3);
+
+        Some(self.idx_tile(&tile_pt)? + bit as usize)
     }
 
     pub fn get_pt(&self, pt: &Point) -> Option<bool> {
@@ -54,26 +62,101 @@ impl Matrix {
         }
     }
 
+    /// every pixel with its pixel coordinate, in storage order, so tile by tile
     pub fn iter_enumerate(&self) -> impl Iterator<Item=(Point, &bool)> {
         self.data.iter().enumerate().map(|(index, bit)| {
-            let x = index as i32 % self.px_bounds.w;
-            let y = index as i32 / self.px_bounds.w;
+            let (tile, bit_idx) = (index as i32 / 9, index as i32 % 9);
+            let x = self.px_bounds.x + tile % self.tile_bounds.w * 3 + bit_idx % 3;
+            let y = self.px_bounds.y + tile / self.tile_bounds.w * 3 + bit_idx / 3;
             (Point { x, y }, bit)
         })
     }
 
+    /// every tile exactly once, row by row
     pub fn iter_tiles_enumerate(&self) -> impl Iterator<Item=(Point, &MatrixTile)> {
-        self.data.iter().enumerate().map(|(index, bit)| {
-            let x = index as i32 % self.px_bounds.w;
-            let y = index as i32 / self.px_bounds.w;
-            let tile_pt = Point { x: x / 3, y: y / 3 };
-            let tile = self.tile(&tile_pt).unwrap();
-            (tile_pt, tile)
-        })
+        enumerate_tiles(&self.tile_bounds, &self.data)
+    }
+
+    /// every tile exactly once, row by row
+    pub fn iter_tiles_enumerate_mut(&mut self) -> impl Iterator<Item=(Point, &mut MatrixTile)> {
+        enumerate_tiles_mut(&self.tile_bounds, &mut self.data)
+    }
+
+    /// splits the matrix into bands of `rows` whole tile rows, the last band may be shorter
+    pub fn row_chunks(&self, rows: i32) -> impl Iterator<Item=MatrixRows<'_>> {
+        let bounds = self.tile_bounds.clone();
+        let rows = rows.max(1);
+
+        self.data.chunks((bounds.w * rows * 9).max(1) as usize)
+            .zip((bounds.y..).step_by(rows as usize))
+            .map(move |(data, y)| MatrixRows {
+                bounds: Rect::new(bounds.x, y, bounds.w, data.len() as i32 / (bounds.w * 9)),
+                data,
+            })
+    }
+
+    /// splits the matrix into disjoint bands of `rows` whole tile rows, which can be handed to
+    /// separate threads. the last band may be shorter.
+    pub fn row_chunks_mut(&mut self, rows: i32) -> impl Iterator<Item=MatrixRowsMut<'_>> {
+        let bounds = self.tile_bounds.clone();
+        let rows = rows.max(1);
+
+        self.data.chunks_mut((bounds.w * rows * 9).max(1) as usize)
+            .zip((bounds.y..).step_by(rows as usize))
+            .map(move |(data, y)| MatrixRowsMut {
+                bounds: Rect::new(bounds.x, y, bounds.w, data.len() as i32 / (bounds.w * 9)),
+                data,
+            })
+    }
+
+    /// the rows and the range of each row in `data` covered by the part of `region` inside of
+    /// the matrix
+    fn region_layout(&self, region: &Rect) -> (Rect, std::ops::Range<usize>, std::ops::Range<usize>) {
+        let region = self.tile_bounds.intersection(region);
+        let bounds = &self.tile_bounds;
+
+        let rows = if region.w == 0 {
+            0..0
+        } else {
+            (region.y - bounds.y) as usize..(region.bottom - bounds.y) as usize
+        };
+        let columns = ((region.x - bounds.x) * 9) as usize..((region.right - bounds.x) * 9) as usize;
+
+        (region, rows, columns)
+    }
+
+    /// the tiles inside of `region`, row by row. tiles outside of the matrix are skipped.
+    pub fn iter_region(&self, region: &Rect) -> impl Iterator<Item=(Point, &MatrixTile)> {
+        let (region, rows, columns) = self.region_layout(region);
+        let row_len = (self.tile_bounds.w * 9).max(1) as usize;
+        let x = region.x;
+
+        self.data.chunks_exact(row_len)
+            .zip(self.tile_bounds.y..)
+            .skip(rows.start)
+            .take(rows.len())
+            .flat_map(move |(row, y)| {
+                row[columns.clone()].chunks_exact(9).zip(x..).map(move |(tile, x)| (Point { x, y }, tile))
+            })
+    }
+
+    /// the tiles inside of `region`, row by row. tiles outside of the matrix are skipped.
+    pub fn iter_region_mut(&mut self, region: &Rect) -> impl Iterator<Item=(Point, &mut MatrixTile)> {
+        let (region, rows, columns) = self.region_layout(region);
+        let row_len = (self.tile_bounds.w * 9).max(1) as usize;
+        let x = region.x;
+
+        self.data.chunks_exact_mut(row_len)
+            .zip(self.tile_bounds.y..)
+            .skip(rows.start)
+            .take(rows.len())
+            .flat_map(move |(row, y)| {
+                row[columns.clone()].chunks_exact_mut(9).zip(x..).map(move |(tile, x)| (Point { x, y }, tile))
+            })
     }
 
     pub fn tile(&self, pt: &Point) -> Option<&MatrixTile> {
-        let idx = self.idx_tile(&pt)?;
+        let idx = self.idx_tile(pt)?;
 
         if idx + 9 > self.data.len() {
             return None;
@@ -83,7 +166,7 @@ impl Matrix {
     }
 
     pub fn tile_mut(&mut self, pt: &Point) -> Option<&mut MatrixTile> {
-        let idx = self.idx_tile(&pt)?;
+        let idx = self.idx_tile(pt)?;
 
         if idx + 9 > self.data.len() {
             return None;
@@ -92,131 +175,259 @@ impl Matrix {
         Some(&mut self.data[idx..idx + 9])
     }
 
-    pub fn strip_invalid(&self) -> Matrix {
-        let mut matrix = self.clone();
+    pub fn get_tile(&self, pt: &Point) -> Option<Tile3x3> {
+        let pixels: [bool; 9] = self.tile(pt)?.try_into().ok()?;
+        Some(Tile3x3::from_array(pixels))
+    }
+
+    pub fn set_tile(&mut self, pt: &Point, value: &Tile3x3) {
+        if let Some(tile) = self.tile_mut(pt) {
+            tile.copy_from_slice(&value.to_array())
+        }
+    }
+
+    /// lists every inconsistent tile and the rule it breaks, without repairing anything
+    pub fn validate(&self, tile_set: &[Tile3x3]) -> Vec<Issue> {
+        validate::validate_with(self, tile_set, &ParallelConfig::global())
+    }
 
-        let strip = |pos: &Point, tile: &mut MatrixTile| {
+    pub fn solve_with(&self, lookup: &TileLookup, config: &ParallelConfig) -> Matrix {
+        let mut solved = self.empty_like();
 
-            if tile[C_IDX] == false {
-                return;
+        solved.for_each_tile_mut(config, |pos, tile| {
+            if self.is_occupied(pos) {
+                tile.copy_from_slice(&lookup.tile(occupancy_mask(pos, |pt| self.is_occupied(pt))).to_array());
             }
+        });
+
+        solved
+    }
 
-            // check diagonal neighbour for contiguous fill cases
-            if tile[NW_IDX] {
-                if let Some(neighbour) = self.tile(&pos.north_west()) {
-                    tile[NW_IDX] = neighbour[SE_IDX];
-                }
+    /// calls `f` on every tile, in bands of rows spread across threads for large matrices
+    fn for_each_tile_mut(&mut self, config: &ParallelConfig, f: impl Fn(&Point, &mut MatrixTile) + Sync) {
+        let (w, h) = (self.tile_bounds.w as usize, self.tile_bounds.h as usize);
+
+        if config.is_serial(w * h) {
+            for (pos, tile) in self.iter_tiles_enumerate_mut() {
+                f(&pos, tile);
             }
+            return;
+        }
 
-            if tile[NE_IDX] {
-                if let Some(neighbour) = self.tile(&pos.north_east()) {
-                    tile[NE_IDX] = neighbour[SW_IDX];
-                }
+        let rows = config.chunk_rows(h) as i32;
+        config.for_each(self.row_chunks_mut(rows).collect(), |mut band| {
+            for (pos, tile) in band.iter_tiles_enumerate_mut() {
+                f(&pos, tile);
             }
+        });
+    }
+
+    pub fn strip_invalid_with(&self, config: &ParallelConfig) -> Matrix {
+        let mut stripped = self.clone();
+        stripped.for_each_tile_mut(config, |pos, tile| self.strip_tile(pos, tile));
+        stripped
+    }
+
+    /// strips into `out` instead of a new matrix, reshaping it to the bounds of this matrix.
+    /// once `out` has held a matrix this size, serial stripping doesn't allocate.
+    pub fn strip_invalid_into(&self, out: &mut Matrix, config: &ParallelConfig) {
+        out.tile_bounds.clone_from(&self.tile_bounds);
+        out.px_bounds.clone_from(&self.px_bounds);
+        out.data.clone_from(&self.data);
+
+        out.for_each_tile_mut(config, |pos, tile| self.strip_tile(pos, tile));
+    }
+
+    /// `strip::strip_tile` on the slices of the matrix, `tile` is a copy of the tile at `pos`
+    /// which is stripped in place against the neighbours in this matrix
+    fn strip_tile(&self, pos: &Point, tile: &mut MatrixTile) {
+        if !tile[C_IDX] {
+            tile.fill(false);
+            return;
+        }
 
-            if tile[SW_IDX] {
-                if let Some(neighbour) = self.tile(&pos.south_west()) {
-                    tile[SW_IDX] = neighbour[NE_IDX];
-                }
+        // check diagonal neighbour for contiguous fill cases
+        if tile[NW_IDX] {
+            if let Some(neighbour) = self.tile(&pos.north_west()) {
+                tile[NW_IDX] = neighbour[SE_IDX];
             }
+        }
 
-            if tile[SE_IDX] {
-                if let Some(neighbour) = self.tile(&pos.south_east()) {
-                    tile[SE_IDX] = neighbour[NW_IDX];
-                }
+        if tile[NE_IDX] {
+            if let Some(neighbour) = self.tile(&pos.north_east()) {
+                tile[NE_IDX] = neighbour[SW_IDX];
             }
+        }
 
-            // clear out invalid pixels
-            if let Some(neighbour) = self.tile(&pos.north()) {
-                tile[N_IDX] = tile[N_IDX] & neighbour[C_IDX] & neighbour[S_IDX] & tile[C_IDX];
+        if tile[SW_IDX] {
+            if let Some(neighbour) = self.tile(&pos.south_west()) {
+                tile[SW_IDX] = neighbour[NE_IDX];
+            }
+        }
 
-                tile[NW_IDX] = tile[NW_IDX] & neighbour[C_IDX] & neighbour[SW_IDX] & tile[C_IDX];
-                tile[NE_IDX] = tile[NE_IDX] & neighbour[C_IDX] & neighbour[SE_IDX] & tile[C_IDX];
+        if tile[SE_IDX] {
+            if let Some(neighbour) = self.tile(&pos.south_east()) {
+                tile[SE_IDX] = neighbour[NW_IDX];
             }
+        }
 
-            if let Some(neighbour) = self.tile(&pos.west()) {
-                tile[W_IDX] = tile[W_IDX] & neighbour[C_IDX] & neighbour[E_IDX] & tile[C_IDX];
+        // clear out invalid pixels
+        if let Some(neighbour) = self.tile(&pos.north()) {
+            tile[N_IDX] = tile[N_IDX] & neighbour[C_IDX] & neighbour[S_IDX];
 
-                tile[NW_IDX] = tile[NW_IDX] & neighbour[C_IDX] & neighbour[NE_IDX] & tile[C_IDX];
-                tile[SW_IDX] = tile[SW_IDX] & neighbour[C_IDX] & neighbour[SE_IDX] & tile[C_IDX];
-            }
+            tile[NW_IDX] = tile[NW_IDX] & neighbour[C_IDX] & neighbour[SW_IDX];
+            tile[NE_IDX] = tile[NE_IDX] & neighbour[C_IDX] & neighbour[SE_IDX];
+        }
 
-            if let Some(neighbour) = self.tile(&pos.east()) {
-                tile[E_IDX] = tile[E_IDX] & neighbour[C_IDX] & neighbour[W_IDX] & tile[C_IDX];
+        if let Some(neighbour) = self.tile(&pos.west()) {
+            tile[W_IDX] = tile[W_IDX] & neighbour[C_IDX] & neighbour[E_IDX];
 
-                tile[NE_IDX] = tile[NE_IDX] & neighbour[C_IDX] & neighbour[NW_IDX] & tile[C_IDX];
-                tile[SE_IDX] = tile[SE_IDX] & neighbour[C_IDX] & neighbour[SW_IDX] & tile[C_IDX];
-            }
+            tile[NW_IDX] = tile[NW_IDX] & neighbour[C_IDX] & neighbour[NE_IDX];
+            tile[SW_IDX] = tile[SW_IDX] & neighbour[C_IDX] & neighbour[SE_IDX];
+        }
 
-            if let Some(neighbour) = self.tile(&pos.south()) {
-                tile[S_IDX] = tile[S_IDX] & neighbour[C_IDX] & neighbour[N_IDX] & tile[C_IDX];
+        if let Some(neighbour) = self.tile(&pos.east()) {
+            tile[E_IDX] = tile[E_IDX] & neighbour[C_IDX] & neighbour[W_IDX];
 
-                tile[SW_IDX] = tile[SW_IDX] & neighbour[C_IDX] & neighbour[NW_IDX] & tile[C_IDX];
-                tile[SE_IDX] = tile[SE_IDX] & neighbour[C_IDX] & neighbour[NE_IDX] & tile[C_IDX];
-            }
-        };
+            tile[NE_IDX] = tile[NE_IDX] & neighbour[C_IDX] & neighbour[NW_IDX];
+            tile[SE_IDX] = tile[SE_IDX] & neighbour[C_IDX] & neighbour[SW_IDX];
+        }
 
-        if matrix.data.len() < 64 * 64 * 9 {
-            for y in 0..matrix.px_bounds.h / 3 {
-                for x in 0..matrix.px_bounds.w / 3 {
-                    let pos = Point { x, y };
-                    let tile = matrix.tile_mut(&pos).unwrap();
-                    strip(&pos, tile);
-                }
-            }
+        if let Some(neighbour) = self.tile(&pos.south()) {
+            tile[S_IDX] = tile[S_IDX] & neighbour[C_IDX] & neighbour[N_IDX];
 
-            return matrix
-        }
-
-        // length of a row in px
-        let rows_per_chunk = 32;
-        let chunk_size = (matrix.tile_bounds.w * 9 * rows_per_chunk) as usize;
-        let chunks = matrix.data.chunks_mut(chunk_size);
-
-        thread::scope(|s| {
-            for (chunk_idx, chunk) in chunks.enumerate() {
-                s.spawn(move || {
-                    for y in 0..rows_per_chunk {
-                        for x in 0..matrix.tile_bounds.w {
-                            let pos = Point {
-                                x: x,
-                                y: (y + chunk_idx as i32 * rows_per_chunk)
-                            };
-
-                            let start_idx = (y * matrix.tile_bounds.w * 9 + x*9) as usize;
-                            if start_idx >= chunk.len() && start_idx+9 >= chunk.len() {
-                                return
-                            }
-
-                            let tile: &mut MatrixTile = &mut chunk[start_idx..start_idx+9];
-                            strip(&pos, tile);
-                        }
-                    }
-                });
-            }
-        });
+            tile[SW_IDX] = tile[SW_IDX] & neighbour[C_IDX] & neighbour[NW_IDX];
+            tile[SE_IDX] = tile[SE_IDX] & neighbour[C_IDX] & neighbour[NE_IDX];
+        }
 
-        matrix
+        // a corner only joins when both edges next to it do
+        tile[NW_IDX] = tile[NW_IDX] & tile[N_IDX] & tile[W_IDX];
+        tile[NE_IDX] = tile[NE_IDX] & tile[N_IDX] & tile[E_IDX];
+        tile[SW_IDX] = tile[SW_IDX] & tile[S_IDX] & tile[W_IDX];
+        tile[SE_IDX] = tile[SE_IDX] & tile[S_IDX] & tile[E_IDX];
     }
 }
 
-fn random_tile_from_tile_set(tile_set: &Vec<Tile3x3>) -> Tile3x3 {
-    let mut rng = thread_rng();
-    tile_set.choose(&mut rng).cloned().unwrap()
+/// pairs every tile of tile-major `data` with its point, `data` covering `bounds` row by row
+fn enumerate_tiles<'a>(bounds: &Rect, data: &'a [bool]) -> impl Iterator<Item=(Point, &'a MatrixTile)> {
+    let (x, y, w) = (bounds.x, bounds.y, bounds.w);
+
+    data.chunks_exact(9).enumerate().map(move |(index, tile)| {
+        (Point { x: x + index as i32 % w, y: y + index as i32 / w }, tile)
+    })
 }
 
-pub fn generate_random_matrix(tile_set: &Vec<Tile3x3>, width: u32, height: u32) -> Matrix {
-    let mut matrix = Matrix::new(Rect::new(0, 0, width as i32, height as i32));
+fn enumerate_tiles_mut<'a>(bounds: &Rect, data: &'a mut [bool]) -> impl Iterator<Item=(Point, &'a mut MatrixTile)> {
+    let (x, y, w) = (bounds.x, bounds.y, bounds.w);
+
+    data.chunks_exact_mut(9).enumerate().map(move |(index, tile)| {
+        (Point { x: x + index as i32 % w, y: y + index as i32 / w }, tile)
+    })
+}
+
+/// a band of whole tile rows borrowed from a `Matrix`
+pub struct MatrixRows<'a> {
+    /// the tiles covered by the band
+    pub bounds: Rect,
+    pub data: &'a [bool],
+}
+
+impl MatrixRows<'_> {
+    pub fn iter_tiles_enumerate(&self) -> impl Iterator<Item=(Point, &MatrixTile)> {
+        enumerate_tiles(&self.bounds, self.data)
+    }
+}
+
+/// a band of whole tile rows mutably borrowed from a `Matrix`
+pub struct MatrixRowsMut<'a> {
+    /// the tiles covered by the band
+    pub bounds: Rect,
+    pub data: &'a mut [bool],
+}
+
+impl MatrixRowsMut<'_> {
+    pub fn iter_tiles_enumerate(&self) -> impl Iterator<Item=(Point, &MatrixTile)> {
+        enumerate_tiles(&self.bounds, self.data)
+    }
+
+    pub fn iter_tiles_enumerate_mut(&mut self) -> impl Iterator<Item=(Point, &mut MatrixTile)> {
+        enumerate_tiles_mut(&self.bounds, self.data)
+    }
+}
+
+impl TileGrid for Matrix {
+    fn bounds(&self) -> Rect {
+        self.tile_bounds.clone()
+    }
+
+    fn tile_at(&self, pt: &Point) -> Option<Tile3x3> {
+        self.get_tile(pt)
+    }
+
+    fn put_tile(&mut self, pt: &Point, tile: Tile3x3) {
+        self.set_tile(pt, &tile)
+    }
 
-    for y in 0..height {
-        for x in 0..width {
-            let pt = Point { x: x as i32, y: y as i32 };
-            let random_tile = random_tile_from_tile_set(&tile_set);
-            let tile_slice = matrix.tile_mut(&pt).unwrap();
-            tile_slice.copy_from_slice(&random_tile.0[..])
+    fn empty_like(&self) -> Self {
+        Matrix::new(self.tile_bounds.clone())
+    }
+
+    fn is_occupied(&self, pt: &Point) -> bool {
+        self.tile(pt).is_some_and(|tile| tile[C_IDX])
+    }
+
+    fn strip_invalid(&self) -> Self {
+        self.strip_invalid_with(&ParallelConfig::global())
+    }
+
+    fn solve(&self, lookup: &TileLookup) -> Self {
+        self.solve_with(lookup, &ParallelConfig::global())
+    }
+}
+
+impl From<&RectVec> for Matrix {
+    fn from(grid: &RectVec) -> Self {
+        let mut matrix = Matrix::new(grid.bounds.clone());
+
+        for (pos, tile) in grid.iter_enumerate() {
+            matrix.set_tile(&pos, tile);
         }
+
+        matrix
     }
+}
+
+impl From<&Matrix> for RectVec {
+    fn from(matrix: &Matrix) -> Self {
+        let mut grid = RectVec::new(matrix.tile_bounds.clone());
 
-    return matrix;
+        for (pos, tile) in matrix.iter_tiles() {
+            grid.set_pt(&pos, tile);
+        }
+
+        grid
+    }
 }
 
+impl StripInto for Matrix {
+    fn strip_into(&self, out: &mut Self, config: &ParallelConfig) {
+        self.strip_invalid_into(out, config)
+    }
+}
+
+pub fn generate_random_matrix(tile_set: &[Tile3x3], width: u32, height: u32) -> Matrix {
+    let mut matrix = Matrix::new(Rect::new(0, 0, width as i32, height as i32));
+    random::fill_random(&mut matrix, tile_set);
+    matrix
+}
+
+pub fn generate_random_matrix_with_rng<R: Rng + ?Sized>(tile_set: &[Tile3x3], width: u32, height: u32, rng: &mut R) -> Matrix {
+    let mut matrix = Matrix::new(Rect::new(0, 0, width as i32, height as i32));
+    random::fill_random_with(&mut matrix, tile_set, rng);
+    matrix
+}
+
+/// the same seed always generates the same matrix, and the same tiles as `generate_test_grid_seeded`
+pub fn generate_random_matrix_seeded(tile_set: &[Tile3x3], width: u32, height: u32, seed: u64) -> Matrix {
+    generate_random_matrix_with_rng(tile_set, width, height, &mut random::seeded_rng(seed))
+}
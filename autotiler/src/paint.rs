@@ -0,0 +1,141 @@
+use crate::point::Point;
+use crate::tile_grid::TileGrid;
+use crate::tile::{Tile3x3, C_IDX, E_IDX, N_IDX, NE_IDX, NW_IDX, S_IDX, SE_IDX, SW_IDX, W_IDX};
+
+/// the bit on `from` which points at the orthogonally adjacent `to`
+fn edge_idx(from: &Point, to: &Point) -> Option<usize> {
+    match (to.x - from.x, to.y - from.y) {
+        (0, -1) => Some(N_IDX),
+        (-1, 0) => Some(W_IDX),
+        (1, 0) => Some(E_IDX),
+        (0, 1) => Some(S_IDX),
+        _ => None,
+    }
+}
+
+/// opens the edge between two orthogonally adjacent tiles on both sides.
+/// nothing is opened if either tile lies outside of the grid.
+fn join_edge<G: TileGrid>(grid: &mut G, a: &Point, b: &Point) {
+    let (Some(mut tile_a), Some(mut tile_b)) = (grid.tile_at(a), grid.tile_at(b)) else {
+        return;
+    };
+
+    tile_a.set(edge_idx(a, b).expect("tiles to be adjacent"), true);
+    tile_b.set(edge_idx(b, a).expect("tiles to be adjacent"), true);
+
+    grid.put_tile(a, tile_a);
+    grid.put_tile(b, tile_b);
+}
+
+/// fills the 2x2 block whose top left tile is at `pt` when all four tiles are occupied,
+/// joining every edge in the block and the corners that meet in its middle.
+fn join_block<G: TileGrid>(grid: &mut G, pt: &Point) {
+    let nw = *pt;
+    let ne = pt.east();
+    let sw = pt.south();
+    let se = pt.south_east();
+
+    if ![nw, ne, sw, se].iter().all(|pt| grid.is_occupied(pt)) {
+        return;
+    }
+
+    join_edge(grid, &nw, &ne);
+    join_edge(grid, &nw, &sw);
+    join_edge(grid, &ne, &se);
+    join_edge(grid, &sw, &se);
+
+    for (pt, corner_idx) in [(nw, SE_IDX), (ne, SW_IDX), (sw, NE_IDX), (se, NW_IDX)] {
+        if let Some(mut tile) = grid.tile_at(&pt) {
+            tile.set(corner_idx, true);
+            grid.put_tile(&pt, tile);
+        }
+    }
+}
+
+/// the points visited when walking from one point to another, first along x then along y.
+/// consecutive points are always orthogonal neighbours, so every step can be joined by an edge.
+pub fn path_points(from: Point, to: Point) -> Vec<Point> {
+    let mut points = vec![from];
+    let mut pt = from;
+
+    while pt.x != to.x {
+        pt.x += (to.x - pt.x).signum();
+        points.push(pt);
+    }
+
+    while pt.y != to.y {
+        pt.y += (to.y - pt.y).signum();
+        points.push(pt);
+    }
+
+    points
+}
+
+/// places a tile on every point of the path and opens the edges between consecutive tiles.
+/// tiles which are not on the path are left untouched, as are the other bits of path tiles.
+pub fn paint_path<G: TileGrid>(grid: &mut G, from: Point, to: Point) {
+    let points = path_points(from, to);
+
+    for pt in &points {
+        if let Some(mut tile) = grid.tile_at(pt) {
+            tile.set(C_IDX, true);
+            grid.put_tile(pt, tile);
+        }
+    }
+
+    for step in points.windows(2) {
+        join_edge(grid, &step[0], &step[1]);
+    }
+}
+
+/// places a tile at `pt` and merges it with all of its occupied neighbours into one blob.
+/// only the tile and its eight neighbours are modified.
+pub fn paint_fill<G: TileGrid>(grid: &mut G, pt: Point) {
+    let Some(mut tile) = grid.tile_at(&pt) else {
+        return;
+    };
+
+    tile.set(C_IDX, true);
+    grid.put_tile(&pt, tile);
+
+    for neighbour in [pt.north(), pt.west(), pt.east(), pt.south()] {
+        if grid.is_occupied(&neighbour) {
+            join_edge(grid, &pt, &neighbour);
+        }
+    }
+
+    // the four 2x2 blocks which contain the new tile
+    for block in [pt.north_west(), pt.north(), pt.west(), pt] {
+        join_block(grid, &block);
+    }
+}
+
+/// removes the tile at `pt` and clears every bit of the eight surrounding tiles which pointed
+/// into the hole, including the corners which relied on the removed tile.
+pub fn erase<G: TileGrid>(grid: &mut G, pt: Point) {
+    if grid.tile_at(&pt).is_none() {
+        return;
+    }
+
+    grid.put_tile(&pt, Tile3x3::default());
+
+    let neighbours = [
+        (pt.north_west(), [SE_IDX].as_slice()),
+        (pt.north(), [S_IDX, SW_IDX, SE_IDX].as_slice()),
+        (pt.north_east(), [SW_IDX].as_slice()),
+        (pt.west(), [E_IDX, NE_IDX, SE_IDX].as_slice()),
+        (pt.east(), [W_IDX, NW_IDX, SW_IDX].as_slice()),
+        (pt.south_west(), [NE_IDX].as_slice()),
+        (pt.south(), [N_IDX, NW_IDX, NE_IDX].as_slice()),
+        (pt.south_east(), [NW_IDX].as_slice()),
+    ];
+
+    for (neighbour, bits) in neighbours {
+        if let Some(mut tile) = grid.tile_at(&neighbour) {
+            for idx in bits {
+                tile.set(*idx, false);
+            }
+            grid.put_tile(&neighbour, tile);
+        }
+    }
+}
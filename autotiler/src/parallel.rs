@@ -0,0 +1,148 @@
+use std::sync::{Mutex, RwLock};
+
+/// how whole grid operations are split across threads. every operation produces the same result
+/// whatever the configuration, it only changes how the work is scheduled.
+///
+/// with the `rayon` feature the work runs on a shared, work-stealing rayon pool. without it every
+/// parallel operation spawns its own scoped threads, which pull chunks from a shared queue and
+/// exit when it's empty. either way no more than `max_threads` threads are used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParallelConfig {
+    /// tile rows handed to a thread at a time, 0 picks them from the grid size and thread count
+    pub chunk_rows: usize,
+    /// grids with fewer tiles than this are processed on the calling thread
+    pub serial_threshold: usize,
+    /// the most threads used at once, 0 uses one per core
+    pub max_threads: usize,
+}
+
+impl Default for ParallelConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+static GLOBAL: RwLock<ParallelConfig> = RwLock::new(ParallelConfig::DEFAULT);
+
+impl ParallelConfig {
+    pub const DEFAULT: ParallelConfig = ParallelConfig {
+        chunk_rows: 0,
+        serial_threshold: 64 * 64,
+        max_threads: 0,
+    };
+
+    /// never uses any threads
+    pub fn serial() -> Self {
+        Self {
+            serial_threshold: usize::MAX,
+            max_threads: 1,
+            ..Self::DEFAULT
+        }
+    }
+
+    /// the configuration used by operations which aren't given one
+    pub fn global() -> ParallelConfig {
+        GLOBAL.read().unwrap_or_else(|err| err.into_inner()).clone()
+    }
+
+    /// replaces the configuration used by operations which aren't given one, e.g. to cap the
+    /// number of threads for an application which has other work to do
+    pub fn set_global(config: ParallelConfig) {
+        *GLOBAL.write().unwrap_or_else(|err| err.into_inner()) = config;
+    }
+
+    /// the number of threads work is split across
+    pub fn threads(&self) -> usize {
+        match self.max_threads {
+            0 => num_cpus::get(),
+            max => max,
+        }
+    }
+
+    /// whether a grid of `tiles` tiles is processed on the calling thread
+    pub fn is_serial(&self, tiles: usize) -> bool {
+        tiles < self.serial_threshold || self.threads() <= 1
+    }
+
+    /// the number of rows per chunk for a grid of `rows` rows. when picked automatically every
+    /// thread gets a few chunks, so threads which finish early can take over the remaining work.
+    pub fn chunk_rows(&self, rows: usize) -> usize {
+        match self.chunk_rows {
+            0 => rows.div_ceil(self.threads() * 4).max(1),
+            chunk_rows => chunk_rows,
+        }
+    }
+
+    /// calls `f` on every item, spread across the threads
+    pub fn for_each<T: Send>(&self, items: Vec<T>, f: impl Fn(T) + Sync) {
+        self.map(items, f);
+    }
+
+    /// calls `f` on every item, spread across the threads, and returns the results in the order
+    /// of the items
+    pub fn map<T: Send, R: Send>(&self, items: Vec<T>, f: impl Fn(T) -> R + Sync) -> Vec<R> {
+        if self.threads() <= 1 || items.len() <= 1 {
+            return items.into_iter().map(f).collect();
+        }
+
+        self.map_parallel(items, f)
+    }
+
+    #[cfg(feature = "rayon")]
+    fn map_parallel<T: Send, R: Send>(&self, items: Vec<T>, f: impl Fn(T) -> R + Sync) -> Vec<R> {
+        use rayon::prelude::*;
+
+        pool(self.threads()).install(|| items.into_par_iter().map(&f).collect())
+    }
+
+    /// not a pool: fresh OS threads are spawned on every call and take items one at a time from
+    /// a mutex guarded queue. spawning costs far more than stripping a small grid, which is what
+    /// `serial_threshold` is for.
+    #[cfg(not(feature = "rayon"))]
+    fn map_parallel<T: Send, R: Send>(&self, items: Vec<T>, f: impl Fn(T) -> R + Sync) -> Vec<R> {
+        let count = items.len();
+        let queue = Mutex::new(items.into_iter().enumerate());
+        let results = Mutex::new(Vec::with_capacity(count));
+
+        std::thread::scope(|s| {
+            for _ in 0..self.threads().min(count) {
+                s.spawn(|| loop {
+                    let Some((idx, item)) = queue.lock().unwrap().next() else {
+                        break;
+                    };
+
+                    let result = f(item);
+                    results.lock().unwrap().push((idx, result));
+                });
+            }
+        });
+
+        let mut results = results.into_inner().unwrap();
+        results.sort_unstable_by_key(|(idx, _)| *idx);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+}
+
+/// the shared pool, rebuilt when the number of threads changes
+#[cfg(feature = "rayon")]
+fn pool(threads: usize) -> std::sync::Arc<rayon::ThreadPool> {
+    use std::sync::Arc;
+
+    static POOL: Mutex<Option<(usize, Arc<rayon::ThreadPool>)>> = Mutex::new(None);
+
+    let mut pool = POOL.lock().unwrap_or_else(|err| err.into_inner());
+
+    match pool.as_ref() {
+        Some((pool_threads, pool)) if *pool_threads == threads => pool.clone(),
+        _ => {
+            let built = Arc::new(rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .thread_name(|idx| format!("autotiler-{idx}"))
+                .build()
+                .expect("to build the thread pool"));
+
+            *pool = Some((threads, built.clone()));
+            built
+        }
+    }
+}
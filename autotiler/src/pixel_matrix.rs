@@ -0,0 +1,175 @@
+use crate::matrix::Matrix;
+use crate::point::Point;
+use crate::rect::Rect;
+use crate::tile::Tile3x3;
+use crate::tile_grid::TileGrid;
+
+/// the pixels of a grid of tiles as a row-major image of `px_bounds`, the pixel-major
+/// counterpart of the tile-major `Matrix`. the bits a tile is stripped against are the pixels
+/// right next to its own, so stripping reads neighbouring pixels instead of neighbouring tiles.
+#[derive(Clone)]
+pub struct PixelMatrix {
+    pub data: Vec<bool>,
+    pub px_bounds: Rect,
+    pub tile_bounds: Rect,
+}
+
+impl PixelMatrix {
+    pub fn new(bounds: Rect) -> Self {
+        let px_bounds = Rect::new(bounds.x * 3, bounds.y * 3, bounds.w * 3, bounds.h * 3);
+        let data = vec![false; (px_bounds.w * px_bounds.h) as usize];
+        Self {
+            data,
+            px_bounds,
+            tile_bounds: bounds,
+        }
+    }
+
+    pub fn idx(&self, pt: &Point) -> Option<usize> {
+        if self.px_bounds.contains(pt) {
+            Some(((pt.y - self.px_bounds.y) * self.px_bounds.w + pt.x - self.px_bounds.x) as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_pt(&self, pt: &Point) -> Option<bool> {
+        let idx = self.idx(pt)?;
+        Some(self.data[idx])
+    }
+
+    pub fn set_pt(&mut self, pt: &Point, value: bool) {
+        if let Some(idx) = self.idx(pt) {
+            self.data[idx] = value
+        }
+    }
+
+    /// every pixel with its pixel coordinate, row by row
+    pub fn iter_enumerate(&self) -> impl Iterator<Item=(Point, &bool)> {
+        self.data.iter().enumerate().map(|(index, bit)| {
+            let x = self.px_bounds.x + index as i32 % self.px_bounds.w;
+            let y = self.px_bounds.y + index as i32 / self.px_bounds.w;
+            (Point { x, y }, bit)
+        })
+    }
+
+    /// the top left pixel of a tile
+    fn tile_origin(pt: &Point) -> Point {
+        Point { x: pt.x * 3, y: pt.y * 3 }
+    }
+
+    pub fn get_tile(&self, pt: &Point) -> Option<Tile3x3> {
+        if !self.tile_bounds.contains(pt) {
+            return None;
+        }
+
+        let origin = Self::tile_origin(pt);
+        let mut tile = Tile3x3::EMPTY;
+
+        for bit in 0..9 {
+            let px = Point { x: origin.x + bit as i32 % 3, y: origin.y + bit as i32 / 3 };
+            tile.set(bit, self.get_pt(&px)?);
+        }
+
+        Some(tile)
+    }
+
+    pub fn set_tile(&mut self, pt: &Point, value: &Tile3x3) {
+        if !self.tile_bounds.contains(pt) {
+            return;
+        }
+
+        let origin = Self::tile_origin(pt);
+
+        for bit in 0..9 {
+            let px = Point { x: origin.x + bit as i32 % 3, y: origin.y + bit as i32 / 3 };
+            self.set_pt(&px, value.get(bit));
+        }
+    }
+}
+
+impl TileGrid for PixelMatrix {
+    fn bounds(&self) -> Rect {
+        self.tile_bounds.clone()
+    }
+
+    fn tile_at(&self, pt: &Point) -> Option<Tile3x3> {
+        self.get_tile(pt)
+    }
+
+    fn put_tile(&mut self, pt: &Point, tile: Tile3x3) {
+        self.set_tile(pt, &tile)
+    }
+
+    fn empty_like(&self) -> Self {
+        PixelMatrix::new(self.tile_bounds.clone())
+    }
+
+    /// the same result as `Matrix::strip_invalid`, working on the pixels directly
+    fn strip_invalid(&self) -> Self {
+        let mut stripped = PixelMatrix::new(self.tile_bounds.clone());
+
+        for pos in self.tile_bounds.points() {
+            let centre = Point { x: pos.x * 3 + 1, y: pos.y * 3 + 1 };
+
+            // pixels outside of the matrix belong to missing neighbours, which are ignored
+            let px = |dx: i32, dy: i32| self.get_pt(&Point { x: centre.x + dx, y: centre.y + dy }).unwrap_or(true);
+
+            if !px(0, 0) {
+                continue;
+            }
+
+            // an edge needs the facing edge and the centre of the neighbour behind it
+            let edge = |dx: i32, dy: i32| px(dx, dy) & px(dx * 2, dy * 2) & px(dx * 3, dy * 3);
+
+            // a corner needs the facing corners of all three neighbours around it, and its own edges
+            let corner = |dx: i32, dy: i32| {
+                px(dx, dy)
+                    & px(dx * 2, dy * 2)
+                    & px(0, dy * 3) & px(dx, dy * 2)
+                    & px(dx * 3, 0) & px(dx * 2, dy)
+                    & edge(0, dy) & edge(dx, 0)
+            };
+
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let value = match (dx, dy) {
+                        (0, 0) => true,
+                        (0, _) | (_, 0) => edge(dx, dy),
+                        _ => corner(dx, dy),
+                    };
+
+                    stripped.set_pt(&Point { x: centre.x + dx, y: centre.y + dy }, value);
+                }
+            }
+        }
+
+        stripped
+    }
+}
+
+/// swizzles tile-major storage into a row-major image
+impl From<&Matrix> for PixelMatrix {
+    fn from(matrix: &Matrix) -> Self {
+        let mut pixels = PixelMatrix::new(matrix.tile_bounds.clone());
+
+        for (pt, bit) in matrix.iter_enumerate() {
+            pixels.set_pt(&pt, *bit);
+        }
+
+        pixels
+    }
+}
+
+/// swizzles a row-major image back into tile-major storage
+impl From<&PixelMatrix> for Matrix {
+    fn from(pixels: &PixelMatrix) -> Self {
+        let mut matrix = Matrix::new(pixels.tile_bounds.clone());
+
+        for (pt, bit) in pixels.iter_enumerate() {
+            matrix.set_pt(&pt, *bit);
+        }
+
+        matrix
+    }
+}
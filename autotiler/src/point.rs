@@ -1,3 +1,4 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Point {
     pub x: i32,
     pub y: i32,
@@ -59,4 +60,13 @@ impl Point {
             y: self.y,
         }
     }
+
+    /// the 3x3 block centred on this point, in the same order as the bits of a tile
+    pub fn neighbourhood(&self) -> [Point; 9] {
+        [
+            self.north_west(), self.north(), self.north_east(),
+            self.west(), *self, self.east(),
+            self.south_west(), self.south(), self.south_east(),
+        ]
+    }
 }
@@ -0,0 +1,57 @@
+use rand::prelude::*;
+use crate::parallel::ParallelConfig;
+use crate::point::Point;
+use crate::tile::Tile3x3;
+use crate::tile_grid::TileGrid;
+
+/// a rng which produces the same sequence for the same seed, for a given version of `rand`
+pub fn seeded_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+fn random_tile_from_tile_set<R: Rng + ?Sized>(tile_set: &[Tile3x3], rng: &mut R) -> Tile3x3 {
+    tile_set.choose(rng).copied().unwrap()
+}
+
+/// overwrites every tile in the bounds of the grid with a random tile from the tile set, row by row
+pub fn fill_random<G: TileGrid>(grid: &mut G, tile_set: &[Tile3x3]) {
+    fill_random_with(grid, tile_set, &mut thread_rng())
+}
+
+/// like [`fill_random`], but draws from `rng`. grids with the same bounds are filled with the
+/// same tiles for the same rng state, whatever their storage layout.
+pub fn fill_random_with<G: TileGrid, R: Rng + ?Sized>(grid: &mut G, tile_set: &[Tile3x3], rng: &mut R) {
+    for pt in grid.bounds().points() {
+        grid.put_tile(&pt, random_tile_from_tile_set(tile_set, rng))
+    }
+}
+
+/// the seed of a single row for [`fill_random_rows`]
+fn row_seed(seed: u64, y: i32) -> u64 {
+    seed ^ (y as i64 as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+}
+
+/// like [`fill_random_with`], but every row draws from its own rng seeded from `seed` and the
+/// row, so rows can be generated in parallel. the same seed always fills the same tiles, however
+/// many threads are used.
+pub fn fill_random_rows<G: TileGrid>(grid: &mut G, tile_set: &[Tile3x3], seed: u64, config: &ParallelConfig) {
+    let bounds = grid.bounds();
+    let rows: Vec<i32> = (bounds.y..bounds.bottom).collect();
+
+    let fill_row = |y: i32| {
+        let mut rng = seeded_rng(row_seed(seed, y));
+        (bounds.x..bounds.right).map(|_| random_tile_from_tile_set(tile_set, &mut rng)).collect::<Vec<_>>()
+    };
+
+    let tiles = if config.is_serial((bounds.w * bounds.h) as usize) {
+        rows.iter().copied().map(fill_row).collect()
+    } else {
+        config.map(rows.clone(), fill_row)
+    };
+
+    for (y, row) in rows.into_iter().zip(tiles) {
+        for (x, tile) in (bounds.x..).zip(row) {
+            grid.put_tile(&Point { x, y }, tile);
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::point::Point;
 
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Rect {
     pub x: i32,
     pub right: i32,
@@ -27,4 +27,20 @@ impl Rect {
             pt.y >= self.y &&
             pt.y < self.bottom
     }
-}
\ No newline at end of file
+
+    /// the overlap of both rects, empty if they don't overlap
+    pub fn intersection(&self, other: &Rect) -> Rect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right.min(other.right).max(x);
+        let bottom = self.bottom.min(other.bottom).max(y);
+
+        Rect::new(x, y, right - x, bottom - y)
+    }
+
+    /// every point inside the rect, row by row
+    pub fn points(&self) -> impl Iterator<Item=Point> {
+        let (x, right) = (self.x, self.right);
+        (self.y..self.bottom).flat_map(move |y| (x..right).map(move |x| Point { x, y }))
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use crate::grid::RectVec;
+use crate::lookup::TileLookup;
+use crate::tile_grid::TileGrid;
+use crate::point::Point;
+use crate::rect::Rect;
+use crate::tile::Tile3x3;
+
+/// a bounded grid which only stores its non-empty tiles, for maps which are mostly empty
+#[derive(Clone)]
+pub struct SparseGrid {
+    tiles: HashMap<Point, Tile3x3>,
+    pub bounds: Rect,
+}
+
+impl SparseGrid {
+    pub fn new(bounds: Rect) -> Self {
+        Self {
+            tiles: HashMap::new(),
+            bounds,
+        }
+    }
+
+    /// the number of stored, non-empty tiles
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// `None` outside of the bounds, unstored tiles inside of them are empty
+    pub fn get_pt(&self, pt: &Point) -> Option<&Tile3x3> {
+        if !self.bounds.contains(pt) {
+            return None;
+        }
+
+        Some(self.tiles.get(pt).unwrap_or(&Tile3x3::EMPTY))
+    }
+
+    /// writing an empty tile removes it from storage
+    pub fn set_pt(&mut self, pt: &Point, value: Tile3x3) {
+        if !self.bounds.contains(pt) {
+            return;
+        }
+
+        if value.is_empty() {
+            self.tiles.remove(pt);
+        } else {
+            self.tiles.insert(*pt, value);
+        }
+    }
+
+    /// only the stored, non-empty tiles, in no particular order
+    pub fn iter_enumerate(&self) -> impl Iterator<Item=(Point, &Tile3x3)> {
+        self.tiles.iter().map(|(pt, tile)| (*pt, tile))
+    }
+}
+
+impl TileGrid for SparseGrid {
+    fn bounds(&self) -> Rect {
+        self.bounds.clone()
+    }
+
+    fn tile_at(&self, pt: &Point) -> Option<Tile3x3> {
+        self.get_pt(pt).copied()
+    }
+
+    fn put_tile(&mut self, pt: &Point, tile: Tile3x3) {
+        self.set_pt(pt, tile)
+    }
+
+    fn empty_like(&self) -> Self {
+        SparseGrid::new(self.bounds.clone())
+    }
+
+    fn iter_tiles(&self) -> impl Iterator<Item=(Point, Tile3x3)> + '_ {
+        self.iter_enumerate().map(|(pt, tile)| (pt, *tile))
+    }
+}
+
+impl From<&RectVec> for SparseGrid {
+    fn from(grid: &RectVec) -> Self {
+        let mut sparse = SparseGrid::new(grid.bounds.clone());
+
+        for (pos, tile) in grid.iter_enumerate() {
+            sparse.set_pt(&pos, *tile);
+        }
+
+        sparse
+    }
+}
+
+impl From<&SparseGrid> for RectVec {
+    fn from(sparse: &SparseGrid) -> Self {
+        let mut grid = RectVec::new(sparse.bounds.clone());
+
+        for (pos, tile) in sparse.iter_enumerate() {
+            grid.set_pt(&pos, *tile);
+        }
+
+        grid
+    }
+}
+
+/// a grid backed by whichever storage suits how much of it is occupied
+#[derive(Clone)]
+pub enum TileStorage {
+    Dense(RectVec),
+    Sparse(SparseGrid),
+}
+
+impl TileStorage {
+    /// grids with a smaller fraction of non-empty tiles than this are stored sparsely
+    pub const SPARSE_DENSITY: f32 = 0.1;
+
+    /// an empty grid, which starts out sparse
+    pub fn new(bounds: Rect) -> Self {
+        TileStorage::Sparse(SparseGrid::new(bounds))
+    }
+
+    /// picks the storage for an existing dense grid from its density
+    pub fn from_dense(grid: RectVec) -> Self {
+        TileStorage::Dense(grid).rebalanced()
+    }
+
+    /// the fraction of tiles in the bounds which aren't empty
+    pub fn density(&self) -> f32 {
+        let bounds = self.bounds();
+        let area = bounds.w as f32 * bounds.h as f32;
+        if area == 0.0 {
+            return 0.0;
+        }
+
+        let filled = match self {
+            TileStorage::Dense(grid) => grid.iter_enumerate().filter(|(_, tile)| !tile.is_empty()).count(),
+            TileStorage::Sparse(grid) => grid.len(),
+        };
+
+        filled as f32 / area
+    }
+
+    /// converts to the storage which suits the current density
+    pub fn rebalanced(self) -> Self {
+        let sparse = self.density() < Self::SPARSE_DENSITY;
+
+        match self {
+            TileStorage::Dense(grid) if sparse => TileStorage::Sparse(SparseGrid::from(&grid)),
+            TileStorage::Sparse(grid) if !sparse => TileStorage::Dense(RectVec::from(&grid)),
+            storage => storage,
+        }
+    }
+
+    pub fn is_sparse(&self) -> bool {
+        matches!(self, TileStorage::Sparse(_))
+    }
+
+    pub fn get_pt(&self, pt: &Point) -> Option<&Tile3x3> {
+        match self {
+            TileStorage::Dense(grid) => grid.get_pt(pt),
+            TileStorage::Sparse(grid) => grid.get_pt(pt),
+        }
+    }
+
+    pub fn set_pt(&mut self, pt: &Point, value: Tile3x3) {
+        match self {
+            TileStorage::Dense(grid) => grid.set_pt(pt, value),
+            TileStorage::Sparse(grid) => grid.set_pt(pt, value),
+        }
+    }
+
+    /// every tile for dense storage, only the non-empty tiles for sparse storage
+    pub fn iter_enumerate(&self) -> Box<dyn Iterator<Item=(Point, &Tile3x3)> + '_> {
+        match self {
+            TileStorage::Dense(grid) => Box::new(grid.iter_enumerate()),
+            TileStorage::Sparse(grid) => Box::new(grid.iter_enumerate()),
+        }
+    }
+}
+
+impl TileGrid for TileStorage {
+    fn bounds(&self) -> Rect {
+        match self {
+            TileStorage::Dense(grid) => grid.bounds.clone(),
+            TileStorage::Sparse(grid) => grid.bounds.clone(),
+        }
+    }
+
+    fn tile_at(&self, pt: &Point) -> Option<Tile3x3> {
+        self.get_pt(pt).copied()
+    }
+
+    fn put_tile(&mut self, pt: &Point, tile: Tile3x3) {
+        self.set_pt(pt, tile)
+    }
+
+    /// keeps the current storage kind
+    fn empty_like(&self) -> Self {
+        match self {
+            TileStorage::Dense(grid) => TileStorage::Dense(grid.empty_like()),
+            TileStorage::Sparse(grid) => TileStorage::Sparse(grid.empty_like()),
+        }
+    }
+
+    fn iter_tiles(&self) -> impl Iterator<Item=(Point, Tile3x3)> + '_ {
+        self.iter_enumerate().map(|(pt, tile)| (pt, *tile))
+    }
+
+    /// strips the grid and picks the storage for the result
+    fn strip_invalid(&self) -> Self {
+        match self {
+            TileStorage::Dense(grid) => TileStorage::Dense(grid.strip_invalid()),
+            TileStorage::Sparse(grid) => TileStorage::Sparse(grid.strip_invalid()),
+        }.rebalanced()
+    }
+
+    /// solves the grid and picks the storage for the result
+    fn solve(&self, lookup: &TileLookup) -> Self {
+        match self {
+            TileStorage::Dense(grid) => TileStorage::Dense(grid.solve(lookup)),
+            TileStorage::Sparse(grid) => TileStorage::Sparse(grid.solve(lookup)),
+        }.rebalanced()
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::HashSet;
+use crate::parallel::ParallelConfig;
+use crate::point::Point;
+use crate::tile_grid::TileGrid;
+use crate::tile::{Tile3x3, C_IDX, E_IDX, N_IDX, NE_IDX, NW_IDX, S_IDX, SE_IDX, SW_IDX, W_IDX};
+
+/// strips every bit of `tile` which doesn't form a valid connection with the neighbours of `pos`.
+/// neighbours outside of the grid are ignored.
+///
+/// a tile with a clear centre is stripped to an empty tile, and a corner only survives when
+/// both of the edges next to it survive as well. both rules are applied in every single pass,
+/// so a pass never leaves a tile that [`crate::validate`] rejects on its own bits.
+pub fn strip_tile<G: TileGrid>(grid: &G, pos: &Point, tile: &Tile3x3) -> Tile3x3 {
+    let mut tile = *tile;
+
+    if !tile.get(C_IDX) {
+        return Tile3x3::default();
+    }
+
+    // check diagonal neighbour for contiguous fill cases
+    if tile.get(NW_IDX) {
+        if let Some(neighbour) = grid.tile_at(&pos.north_west()) {
+            tile.set(NW_IDX, neighbour.get(SE_IDX));
+        }
+    }
+
+    if tile.get(NE_IDX) {
+        if let Some(neighbour) = grid.tile_at(&pos.north_east()) {
+            tile.set(NE_IDX, neighbour.get(SW_IDX));
+        }
+    }
+
+    if tile.get(SW_IDX) {
+        if let Some(neighbour) = grid.tile_at(&pos.south_west()) {
+            tile.set(SW_IDX, neighbour.get(NE_IDX));
+        }
+    }
+
+    if tile.get(SE_IDX) {
+        if let Some(neighbour) = grid.tile_at(&pos.south_east()) {
+            tile.set(SE_IDX, neighbour.get(NW_IDX));
+        }
+    }
+
+    // clear out invalid pixels
+    if let Some(neighbour) = grid.tile_at(&pos.north()) {
+        tile.set(N_IDX, tile.get(N_IDX) & neighbour.get(C_IDX) & neighbour.get(S_IDX));
+
+        tile.set(NW_IDX, tile.get(NW_IDX) & neighbour.get(C_IDX) & neighbour.get(SW_IDX));
+        tile.set(NE_IDX, tile.get(NE_IDX) & neighbour.get(C_IDX) & neighbour.get(SE_IDX));
+    }
+
+    if let Some(neighbour) = grid.tile_at(&pos.west()) {
+        tile.set(W_IDX, tile.get(W_IDX) & neighbour.get(C_IDX) & neighbour.get(E_IDX));
+
+        tile.set(NW_IDX, tile.get(NW_IDX) & neighbour.get(C_IDX) & neighbour.get(NE_IDX));
+        tile.set(SW_IDX, tile.get(SW_IDX) & neighbour.get(C_IDX) & neighbour.get(SE_IDX));
+    }
+
+    if let Some(neighbour) = grid.tile_at(&pos.east()) {
+        tile.set(E_IDX, tile.get(E_IDX) & neighbour.get(C_IDX) & neighbour.get(W_IDX));
+
+        tile.set(NE_IDX, tile.get(NE_IDX) & neighbour.get(C_IDX) & neighbour.get(NW_IDX));
+        tile.set(SE_IDX, tile.get(SE_IDX) & neighbour.get(C_IDX) & neighbour.get(SW_IDX));
+    }
+
+    if let Some(neighbour) = grid.tile_at(&pos.south()) {
+        tile.set(S_IDX, tile.get(S_IDX) & neighbour.get(C_IDX) & neighbour.get(N_IDX));
+
+        tile.set(SW_IDX, tile.get(SW_IDX) & neighbour.get(C_IDX) & neighbour.get(NW_IDX));
+        tile.set(SE_IDX, tile.get(SE_IDX) & neighbour.get(C_IDX) & neighbour.get(NE_IDX));
+    }
+
+    // a corner only joins when both edges next to it do
+    tile.set(NW_IDX, tile.get(NW_IDX) & tile.get(N_IDX) & tile.get(W_IDX));
+    tile.set(NE_IDX, tile.get(NE_IDX) & tile.get(N_IDX) & tile.get(E_IDX));
+    tile.set(SW_IDX, tile.get(SW_IDX) & tile.get(S_IDX) & tile.get(W_IDX));
+    tile.set(SE_IDX, tile.get(SE_IDX) & tile.get(S_IDX) & tile.get(E_IDX));
+
+    tile
+}
+
+/// strips every invalidly placed pixel of the grid with [`strip_tile`], reading neighbours from the
+/// unmodified grid. tiles with a clear centre come out empty.
+pub fn strip_invalid<G: TileGrid>(grid: &G) -> G {
+    let mut stripped = grid.empty_like();
+
+    for (pos, tile) in grid.iter_tiles() {
+        if !tile.get(C_IDX) {
+            continue;
+        }
+
+        stripped.put_tile(&pos, strip_tile(grid, &pos, &tile));
+    }
+
+    stripped
+}
+
+/// what a run of [`strip_invalid_fixed_point`] did
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StripStats {
+    /// the number of full passes, including the last one which changed nothing
+    pub iterations: usize,
+    /// the number of tiles which ended up different from the input
+    pub changed: usize,
+}
+
+/// strips the grid again and again until a pass changes nothing. a single pass reads every tile
+/// from the unmodified grid, so a bit removed from one tile can leave a neighbour pointing at it.
+pub fn strip_invalid_fixed_point<G: TileGrid>(grid: &G) -> (G, StripStats) {
+    repeat_to_fixed_point(grid, strip_invalid)
+}
+
+/// repeats a full stripping `pass` until it changes nothing, for grids with their own pass
+pub fn repeat_to_fixed_point<G: TileGrid>(grid: &G, pass: impl Fn(&G) -> G) -> (G, StripStats) {
+    let mut current = pass(grid);
+    let mut iterations = 1;
+    let mut changing = count_changed(grid, &current) > 0;
+
+    while changing {
+        let next = pass(&current);
+        iterations += 1;
+        changing = count_changed(&current, &next) > 0;
+        current = next;
+    }
+
+    let changed = count_changed(grid, &current);
+    (current, StripStats { iterations, changed })
+}
+
+/// stripping never adds bits, so every tile which changed is visited by the original's iterator
+fn count_changed<G: TileGrid>(original: &G, stripped: &G) -> usize {
+    original.iter_tiles()
+        .filter(|(pt, tile)| stripped.tile_at(pt).unwrap_or_default() != *tile)
+        .count()
+}
+
+/// grids which can be stripped into another grid, reusing its allocation
+pub trait StripInto: TileGrid {
+    /// writes the stripped grid into `out`, reshaping it to the bounds of the grid. once `out`
+    /// has held a grid this size, serial stripping doesn't allocate.
+    fn strip_into(&self, out: &mut Self, config: &ParallelConfig);
+}
+
+/// strips grids in place by ping-ponging between the grid and scratch grids owned by the
+/// context. keep one around between frames: once it has stripped a grid this size, serial passes
+/// don't allocate anything.
+///
+/// the context is serial by default. passes with a parallel config do allocate, every pass
+/// collects its bands of rows, and without the `rayon` feature spawns its threads as well.
+pub struct StripContext<G> {
+    scratch: Option<G>,
+    /// only used by fixed point runs, which keep the original grid in `scratch`
+    spare: Option<G>,
+    pub config: ParallelConfig,
+}
+
+impl<G> Default for StripContext<G> {
+    fn default() -> Self {
+        Self::new(ParallelConfig::serial())
+    }
+}
+
+impl<G> StripContext<G> {
+    pub fn new(config: ParallelConfig) -> Self {
+        Self {
+            scratch: None,
+            spare: None,
+            config,
+        }
+    }
+}
+
+impl<G: StripInto> StripContext<G> {
+    /// strips the grid in place, a single pass like [`strip_invalid`]
+    pub fn strip(&mut self, grid: &mut G) {
+        let scratch = self.scratch.get_or_insert_with(|| grid.empty_like());
+
+        grid.strip_into(scratch, &self.config);
+        std::mem::swap(grid, scratch);
+    }
+
+    /// strips the grid in place until a pass changes nothing, like [`strip_invalid_fixed_point`]
+    pub fn strip_to_fixed_point(&mut self, grid: &mut G) -> StripStats {
+        // after the first pass the original grid is left in `scratch`, later passes go through
+        // `spare` so it's still there to count the changed tiles against
+        self.strip(grid);
+        let original = self.scratch.as_ref().unwrap();
+
+        if count_changed(original, grid) == 0 {
+            return StripStats { iterations: 1, changed: 0 };
+        }
+
+        let spare = self.spare.get_or_insert_with(|| grid.empty_like());
+        let mut iterations = 1;
+
+        loop {
+            grid.strip_into(spare, &self.config);
+            std::mem::swap(grid, spare);
+            iterations += 1;
+
+            if count_changed(spare, grid) == 0 {
+                return StripStats { iterations, changed: count_changed(original, grid) };
+            }
+        }
+    }
+}
+
+/// strips the 3x3 neighbourhoods around the dirty points in place. whenever a tile changes its
+/// own neighbourhood is revisited, so the touched region ends up as valid as a full pass would
+/// leave it. returns the points of the tiles which changed, in the order they first changed.
+pub fn strip_dirty<G: TileGrid>(grid: &mut G, dirty: impl IntoIterator<Item=Point>) -> Vec<Point> {
+    let mut queue: Vec<Point> = Vec::new();
+    let mut queued: HashSet<Point> = HashSet::new();
+
+    for pt in dirty {
+        for neighbour in pt.neighbourhood() {
+            if queued.insert(neighbour) {
+                queue.push(neighbour);
+            }
+        }
+    }
+
+    let mut changed = Vec::new();
+    let mut changed_set = HashSet::new();
+
+    while let Some(pt) = queue.pop() {
+        queued.remove(&pt);
+
+        let Some(tile) = grid.tile_at(&pt) else {
+            continue;
+        };
+
+        let stripped = strip_tile(grid, &pt, &tile);
+        if stripped == tile {
+            continue;
+        }
+
+        grid.put_tile(&pt, stripped);
+
+        if changed_set.insert(pt) {
+            changed.push(pt);
+        }
+
+        for neighbour in pt.neighbourhood() {
+            if neighbour != pt && queued.insert(neighbour) {
+                queue.push(neighbour);
+            }
+        }
+    }
+
+    changed
+}
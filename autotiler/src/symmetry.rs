@@ -0,0 +1,93 @@
+use crate::tile::Tile3x3;
+
+/// the eight ways a square tile can be rotated or mirrored onto itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transform {
+    Identity,
+    RotateCw,
+    Rotate180,
+    RotateCcw,
+    FlipH,
+    FlipV,
+    Transpose,
+    /// mirrors along the diagonal from north east to south west
+    AntiTranspose,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::RotateCw,
+        Transform::Rotate180,
+        Transform::RotateCcw,
+        Transform::FlipH,
+        Transform::FlipV,
+        Transform::Transpose,
+        Transform::AntiTranspose,
+    ];
+
+    pub const fn apply(self, tile: Tile3x3) -> Tile3x3 {
+        match self {
+            Transform::Identity => tile,
+            Transform::RotateCw => tile.rotate_cw(),
+            Transform::Rotate180 => tile.rotate_cw().rotate_cw(),
+            Transform::RotateCcw => tile.rotate_ccw(),
+            Transform::FlipH => tile.flip_h(),
+            Transform::FlipV => tile.flip_v(),
+            Transform::Transpose => tile.transpose(),
+            Transform::AntiTranspose => tile.transpose().rotate_cw().rotate_cw(),
+        }
+    }
+
+    /// the transform which undoes this one
+    pub const fn inverse(self) -> Transform {
+        match self {
+            Transform::RotateCw => Transform::RotateCcw,
+            Transform::RotateCcw => Transform::RotateCw,
+            other => other,
+        }
+    }
+}
+
+/// a group of tiles which are all rotations or mirrors of one canonical tile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymmetryClass {
+    /// index of the canonical tile in the tile set
+    pub canonical: usize,
+    /// index of every tile in the class, including the canonical tile, paired with the transform
+    /// which maps that tile onto the canonical one
+    pub members: Vec<(usize, Transform)>,
+}
+
+impl SymmetryClass {
+    /// rebuilds every member of the class from the canonical tile alone
+    pub fn derive(&self, canonical: Tile3x3) -> Vec<(usize, Tile3x3)> {
+        self.members.iter()
+            .map(|(idx, transform)| (*idx, transform.inverse().apply(canonical)))
+            .collect()
+    }
+}
+
+/// groups a tile set into symmetry classes. the first tile of each class, in tile set order, is
+/// its canonical tile. identical tiles land in the same class with [`Transform::Identity`].
+pub fn symmetry_classes(tile_set: &[Tile3x3]) -> Vec<SymmetryClass> {
+    let mut classes: Vec<SymmetryClass> = Vec::new();
+
+    'tiles: for (idx, tile) in tile_set.iter().enumerate() {
+        for class in classes.iter_mut() {
+            let canonical = tile_set[class.canonical];
+
+            if let Some(transform) = Transform::ALL.iter().find(|transform| transform.apply(*tile) == canonical) {
+                class.members.push((idx, *transform));
+                continue 'tiles;
+            }
+        }
+
+        classes.push(SymmetryClass {
+            canonical: idx,
+            members: vec![(idx, Transform::Identity)],
+        });
+    }
+
+    classes
+}
@@ -1,8 +1,11 @@
-use image::io::Reader as ImageReader;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
 
 /// 3x3 = 9 bits, represented as a u16
-#[derive(Debug, Clone, Default)]
-pub struct Tile3x3(pub [bool; 9]);
+///
+/// bit `n` holds the pixel at index `n`, so `1 << N_IDX` is the north pixel. the bits above
+/// the ninth are always clear, which is why the field is private.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Tile3x3(u16);
 
 pub const NW_IDX: usize = 0;
 pub const N_IDX: usize = 1;
@@ -14,7 +17,144 @@ pub const SW_IDX: usize = 6;
 pub const S_IDX: usize = 7;
 pub const SE_IDX: usize = 8;
 
+/// the indices of the eight pixels around the centre, in the order of [`Tile3x3::neighbours`]
+pub const NEIGHBOUR_IDXS: [usize; 8] = [NW_IDX, N_IDX, NE_IDX, W_IDX, E_IDX, SW_IDX, S_IDX, SE_IDX];
+
 impl Tile3x3 {
+    /// all nine bits
+    pub const MASK: u16 = 0b1_1111_1111;
+    pub const CENTRE_MASK: u16 = 1 << C_IDX;
+    pub const EDGE_MASK: u16 = 1 << N_IDX | 1 << W_IDX | 1 << E_IDX | 1 << S_IDX;
+    pub const CORNER_MASK: u16 = 1 << NW_IDX | 1 << NE_IDX | 1 << SW_IDX | 1 << SE_IDX;
+    pub const NEIGHBOUR_MASK: u16 = Self::EDGE_MASK | Self::CORNER_MASK;
+
+    pub const EMPTY: Tile3x3 = Tile3x3(0);
+    pub const FULL: Tile3x3 = Tile3x3(Self::MASK);
+
+    /// bits above the ninth are discarded
+    #[inline]
+    pub const fn from_bits(bits: u16) -> Self {
+        Self(bits & Self::MASK)
+    }
+
+    #[inline]
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    pub const fn from_array(pixels: [bool; 9]) -> Self {
+        let mut bits = 0;
+        let mut i = 0;
+        while i < 9 {
+            bits |= (pixels[i] as u16) << i;
+            i += 1;
+        }
+        Self(bits)
+    }
+
+    pub const fn to_array(self) -> [bool; 9] {
+        let mut pixels = [false; 9];
+        let mut i = 0;
+        while i < 9 {
+            pixels[i] = self.0 >> i & 1 == 1;
+            i += 1;
+        }
+        pixels
+    }
+
+    /// builds a tile from three rows of pixels drawn with `#` for set and anything else for unset
+    pub const fn from_rows(rows: [&[u8; 3]; 3]) -> Self {
+        let mut bits = 0;
+        let mut i = 0;
+        while i < 9 {
+            bits |= ((rows[i / 3][i % 3] == b'#') as u16) << i;
+            i += 1;
+        }
+        Self(bits)
+    }
+
+    /// builds a tile from its centre and the packed mask returned by [`Tile3x3::neighbours`]
+    pub const fn from_neighbours(centre: bool, neighbours: u8) -> Self {
+        let mut bits = (centre as u16) << C_IDX;
+        let mut i = 0;
+        while i < 8 {
+            bits |= ((neighbours >> i & 1) as u16) << NEIGHBOUR_IDXS[i];
+            i += 1;
+        }
+        Self(bits)
+    }
+
+    /// the eight pixels around the centre packed into a byte, in the order of [`NEIGHBOUR_IDXS`]
+    pub const fn neighbours(self) -> u8 {
+        let mut mask = 0;
+        let mut i = 0;
+        while i < 8 {
+            mask |= ((self.0 >> NEIGHBOUR_IDXS[i] & 1) as u8) << i;
+            i += 1;
+        }
+        mask
+    }
+
+    /// a copy of the tile with a single bit changed, panics if `idx` isn't one of the nine bits
+    #[inline]
+    pub const fn with(self, idx: usize, value: bool) -> Self {
+        assert!(idx < 9, "tile bit index out of range");
+        Self(self.0 & !(1 << idx) | (value as u16) << idx)
+    }
+
+    /// only the north, west, east and south pixels
+    #[inline]
+    pub const fn edges(self) -> Self {
+        Self(self.0 & Self::EDGE_MASK)
+    }
+
+    /// only the four corner pixels
+    #[inline]
+    pub const fn corners(self) -> Self {
+        Self(self.0 & Self::CORNER_MASK)
+    }
+
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// builds a new tile where pixel `i` is taken from pixel `source[i]` of this tile
+    const fn permute(self, source: [usize; 9]) -> Self {
+        let mut bits = 0;
+        let mut i = 0;
+        while i < 9 {
+            bits |= (self.0 >> source[i] & 1) << i;
+            i += 1;
+        }
+        Self(bits)
+    }
+
+    /// rotates the tile a quarter turn clockwise, north becomes east
+    pub const fn rotate_cw(self) -> Self {
+        self.permute([SW_IDX, W_IDX, NW_IDX, S_IDX, C_IDX, N_IDX, SE_IDX, E_IDX, NE_IDX])
+    }
+
+    /// rotates the tile a quarter turn counter-clockwise, north becomes west
+    pub const fn rotate_ccw(self) -> Self {
+        self.permute([NE_IDX, E_IDX, SE_IDX, N_IDX, C_IDX, S_IDX, NW_IDX, W_IDX, SW_IDX])
+    }
+
+    /// mirrors the tile left to right, west becomes east
+    pub const fn flip_h(self) -> Self {
+        self.permute([NE_IDX, N_IDX, NW_IDX, E_IDX, C_IDX, W_IDX, SE_IDX, S_IDX, SW_IDX])
+    }
+
+    /// mirrors the tile top to bottom, north becomes south
+    pub const fn flip_v(self) -> Self {
+        self.permute([SW_IDX, S_IDX, SE_IDX, W_IDX, C_IDX, E_IDX, NW_IDX, N_IDX, NE_IDX])
+    }
+
+    /// mirrors the tile along the diagonal from north west to south east, north becomes west
+    pub const fn transpose(self) -> Self {
+        self.permute([NW_IDX, W_IDX, SW_IDX, N_IDX, C_IDX, S_IDX, NE_IDX, E_IDX, SE_IDX])
+    }
+
     #[inline]
     pub fn idx(x: u8, y: u8) -> usize {
         (y * 3 + x) as usize
@@ -30,66 +170,140 @@ impl Tile3x3 {
         self.get(Self::idx(x, y))
     }
 
+    /// panics if `idx` isn't one of the nine bits
     #[inline]
-    pub fn get(&self, idx: usize) -> bool {
-        self.0[idx]
+    pub const fn get(&self, idx: usize) -> bool {
+        assert!(idx < 9, "tile bit index out of range");
+        self.0 >> idx & 1 == 1
     }
 
+    /// panics if `idx` isn't one of the nine bits
     #[inline]
     pub fn set(&mut self, idx: usize, value: bool) {
-        self.0[idx] = value
+        *self = self.with(idx, value)
     }
 }
 
-/// generates a minimal 3x3 tileset based on image data
-pub fn minimal_3x3_tile_set() -> Vec<Tile3x3> {
-    let rows = 12;
-    let cols = 4;
-
-    let mut tiles = Vec::with_capacity(rows * cols);
-
-    let tile_size_px = 64;
-    let chunk_size_px = tile_size_px/3;
-    let chunk_center_px = chunk_size_px/2;
-
-    let img = ImageReader::open("../img/3x3-minimal.png").unwrap();
-    let decoded = img.decode().unwrap();
-    let rgba = decoded.as_rgba8().unwrap();
-
-    //start with a fixed offset so when we move across the tiles we're sampling the center of the 'pixel'
-    let mut sample_y = chunk_center_px;
-    for _ in 0..cols {
-        let mut sample_x = chunk_center_px;
-        for _ in 0..rows {
-            let mut tile : Tile3x3 = Tile3x3([false; 9]);
-
-            // read tile by sampling the center of each chunk
-            let mut i = 0;
-            for chunk_y in 0..3 {
-                for chunk_x in 0..3 {
-                    let sample = rgba.get_pixel(
-                        sample_x + chunk_x * chunk_size_px,
-                        sample_y + chunk_y * chunk_size_px
-                    );
-
-                    // if the chunk has a pixel with white, it's not set
-                    let is_white = sample.0[0] == 255 && sample.0[1] == 255 && sample.0[2] == 255;
-                    tile.set(i, !is_white);
-
-                    i += 1;
-                }
-            }
-
-            tiles.push(tile);
-
-            // move to next x
-            sample_x += tile_size_px;
-        }
+impl From<[bool; 9]> for Tile3x3 {
+    fn from(pixels: [bool; 9]) -> Self {
+        Self::from_array(pixels)
+    }
+}
 
-        // move to next y
-        sample_y += tile_size_px;
+impl From<Tile3x3> for [bool; 9] {
+    fn from(tile: Tile3x3) -> Self {
+        tile.to_array()
     }
+}
+
+impl BitAnd for Tile3x3 {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
 
+impl BitOr for Tile3x3 {
+    type Output = Self;
 
-    return tiles;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitXor for Tile3x3 {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self {
+        Self(self.0 ^ rhs.0)
+    }
+}
+
+impl Not for Tile3x3 {
+    type Output = Self;
+
+    /// only the nine tile bits are inverted
+    fn not(self) -> Self {
+        Self(!self.0 & Self::MASK)
+    }
+}
+
+impl BitAndAssign for Tile3x3 {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0
+    }
+}
+
+impl BitOrAssign for Tile3x3 {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0
+    }
+}
+
+impl BitXorAssign for Tile3x3 {
+    fn bitxor_assign(&mut self, rhs: Self) {
+        self.0 ^= rhs.0
+    }
+}
+
+/// the reference minimal 3x3 tile set from `img/3x3-minimal.png`, read row by row from its
+/// 12 columns and 4 rows. index 22 is the empty tile, the other 47 are every possible blob tile.
+pub const MINIMAL_3X3_TILE_SET: [Tile3x3; 48] = [
+    Tile3x3::from_rows([b"...", b".#.", b".#."]),
+    Tile3x3::from_rows([b"...", b".##", b".#."]),
+    Tile3x3::from_rows([b"...", b"###", b".#."]),
+    Tile3x3::from_rows([b"...", b"##.", b".#."]),
+    Tile3x3::from_rows([b"##.", b"###", b".#."]),
+    Tile3x3::from_rows([b"...", b"###", b".##"]),
+    Tile3x3::from_rows([b"...", b"###", b"##."]),
+    Tile3x3::from_rows([b".##", b"###", b".#."]),
+    Tile3x3::from_rows([b"...", b".##", b".##"]),
+    Tile3x3::from_rows([b".#.", b"###", b"###"]),
+    Tile3x3::from_rows([b"...", b"###", b"###"]),
+    Tile3x3::from_rows([b"...", b"##.", b"##."]),
+
+    Tile3x3::from_rows([b".#.", b".#.", b".#."]),
+    Tile3x3::from_rows([b".#.", b".##", b".#."]),
+    Tile3x3::from_rows([b".#.", b"###", b".#."]),
+    Tile3x3::from_rows([b".#.", b"##.", b".#."]),
+    Tile3x3::from_rows([b".#.", b".##", b".##"]),
+    Tile3x3::from_rows([b".##", b"###", b"###"]),
+    Tile3x3::from_rows([b"##.", b"###", b"###"]),
+    Tile3x3::from_rows([b".#.", b"##.", b"##."]),
+    Tile3x3::from_rows([b".##", b".##", b".##"]),
+    Tile3x3::from_rows([b".##", b"###", b"##."]),
+    Tile3x3::from_rows([b"...", b"...", b"..."]),
+    Tile3x3::from_rows([b"##.", b"###", b"##."]),
+
+    Tile3x3::from_rows([b".#.", b".#.", b"..."]),
+    Tile3x3::from_rows([b".#.", b".##", b"..."]),
+    Tile3x3::from_rows([b".#.", b"###", b"..."]),
+    Tile3x3::from_rows([b".#.", b"##.", b"..."]),
+    Tile3x3::from_rows([b".##", b".##", b".#."]),
+    Tile3x3::from_rows([b"###", b"###", b".##"]),
+    Tile3x3::from_rows([b"###", b"###", b"##."]),
+    Tile3x3::from_rows([b"##.", b"##.", b".#."]),
+    Tile3x3::from_rows([b".##", b"###", b".##"]),
+    Tile3x3::from_rows([b"###", b"###", b"###"]),
+    Tile3x3::from_rows([b"##.", b"###", b".##"]),
+    Tile3x3::from_rows([b"##.", b"##.", b"##."]),
+
+    Tile3x3::from_rows([b"...", b".#.", b"..."]),
+    Tile3x3::from_rows([b"...", b".##", b"..."]),
+    Tile3x3::from_rows([b"...", b"###", b"..."]),
+    Tile3x3::from_rows([b"...", b"##.", b"..."]),
+    Tile3x3::from_rows([b".#.", b"###", b"##."]),
+    Tile3x3::from_rows([b".##", b"###", b"..."]),
+    Tile3x3::from_rows([b"##.", b"###", b"..."]),
+    Tile3x3::from_rows([b".#.", b"###", b".##"]),
+    Tile3x3::from_rows([b".##", b".##", b"..."]),
+    Tile3x3::from_rows([b"###", b"###", b"..."]),
+    Tile3x3::from_rows([b"###", b"###", b".#."]),
+    Tile3x3::from_rows([b"##.", b"##.", b"..."]),
+];
+
+/// the minimal 3x3 tileset, see [`MINIMAL_3X3_TILE_SET`]
+pub fn minimal_3x3_tile_set() -> Vec<Tile3x3> {
+    MINIMAL_3X3_TILE_SET.to_vec()
 }
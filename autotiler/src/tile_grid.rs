@@ -0,0 +1,121 @@
+use crate::lookup::{self, TileLookup};
+use crate::paint;
+use crate::point::Point;
+use crate::rect::Rect;
+use crate::strip::{self, StripStats};
+use crate::tile::{Tile3x3, C_IDX};
+
+/// tile access shared by every grid representation, so that stripping, solving and painting
+/// are only written once and can be benchmarked across storage layouts
+pub trait TileGrid {
+    /// the area of the grid which holds tiles
+    fn bounds(&self) -> Rect;
+
+    /// `None` for points the grid can't hold a tile for
+    fn tile_at(&self, pt: &Point) -> Option<Tile3x3>;
+
+    /// writes outside of the grid are ignored
+    fn put_tile(&mut self, pt: &Point, tile: Tile3x3);
+
+    /// a grid of the same shape and kind with only empty tiles
+    fn empty_like(&self) -> Self where Self: Sized;
+
+    fn is_occupied(&self, pt: &Point) -> bool {
+        self.tile_at(pt).is_some_and(|tile| tile.get(C_IDX))
+    }
+
+    /// the 3x3 block of tiles centred on `pt`, in the same order as the bits of a tile
+    fn neighbourhood(&self, pt: &Point) -> [Option<Tile3x3>; 9] {
+        pt.neighbourhood().map(|neighbour| self.tile_at(&neighbour))
+    }
+
+    /// every tile which might not be empty. dense grids yield every tile in their bounds.
+    fn iter_tiles(&self) -> impl Iterator<Item=(Point, Tile3x3)> + '_ {
+        self.bounds().points().filter_map(|pt| Some((pt, self.tile_at(&pt)?)))
+    }
+
+    /// path mode: connects `from` to `to` without modifying tiles off the path
+    fn paint_path(&mut self, from: Point, to: Point) where Self: Sized {
+        paint::paint_path(self, from, to)
+    }
+
+    /// fill mode: places a tile which merges into a blob with all of its neighbours
+    fn paint_fill(&mut self, pt: Point) where Self: Sized {
+        paint::paint_fill(self, pt)
+    }
+
+    /// erase mode: removes a tile and disconnects its neighbours from the hole
+    fn erase(&mut self, pt: Point) where Self: Sized {
+        paint::erase(self, pt)
+    }
+
+    /// re-strips only the neighbourhoods of the given points in place, returning the changed tiles
+    fn strip_invalid_points(&mut self, points: &[Point]) -> Vec<Point> where Self: Sized {
+        strip::strip_dirty(self, points.iter().copied())
+    }
+
+    /// re-strips only the tiles in and around `rect` in place, returning the changed tiles
+    fn strip_invalid_rect(&mut self, rect: &Rect) -> Vec<Point> where Self: Sized {
+        strip::strip_dirty(self, rect.points())
+    }
+
+    /// a single pass of `strip::strip_tile` over every tile. grids with a faster pass of their
+    /// own override it, the result is the same.
+    fn strip_invalid(&self) -> Self where Self: Sized {
+        strip::strip_invalid(self)
+    }
+
+    /// repeats `strip_invalid` until a pass changes nothing
+    fn strip_invalid_fixed_point(&self) -> (Self, StripStats) where Self: Sized {
+        strip::repeat_to_fixed_point(self, Self::strip_invalid)
+    }
+
+    /// table driven solve, every occupied tile is replaced by the tile which joins it with all
+    /// of its occupied neighbours
+    fn solve(&self, lookup: &TileLookup) -> Self where Self: Sized {
+        lookup::solve(self, lookup)
+    }
+}
+
+/// a grid of rows, with its origin at (0, 0)
+impl TileGrid for [Vec<Tile3x3>] {
+    fn bounds(&self) -> Rect {
+        Rect::new(0, 0, self.first().map_or(0, |row| row.len()) as i32, self.len() as i32)
+    }
+
+    fn tile_at(&self, pt: &Point) -> Option<Tile3x3> {
+        if pt.x < 0 || pt.y < 0 {
+            return None;
+        }
+
+        self.get(pt.y as usize)?.get(pt.x as usize).copied()
+    }
+
+    fn put_tile(&mut self, pt: &Point, tile: Tile3x3) {
+        if pt.x < 0 || pt.y < 0 {
+            return;
+        }
+
+        if let Some(slot) = self.get_mut(pt.y as usize).and_then(|row| row.get_mut(pt.x as usize)) {
+            *slot = tile
+        }
+    }
+}
+
+impl TileGrid for Vec<Vec<Tile3x3>> {
+    fn bounds(&self) -> Rect {
+        self.as_slice().bounds()
+    }
+
+    fn tile_at(&self, pt: &Point) -> Option<Tile3x3> {
+        self.as_slice().tile_at(pt)
+    }
+
+    fn put_tile(&mut self, pt: &Point, tile: Tile3x3) {
+        self.as_mut_slice().put_tile(pt, tile)
+    }
+
+    fn empty_like(&self) -> Self {
+        self.iter().map(|row| vec![Tile3x3::EMPTY; row.len()]).collect()
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use crate::parallel::ParallelConfig;
+use crate::point::Point;
+use crate::rect::Rect;
+use crate::tile::{Tile3x3, C_IDX, E_IDX, N_IDX, NE_IDX, NEIGHBOUR_IDXS, NW_IDX, S_IDX, SE_IDX, SW_IDX, W_IDX};
+use crate::tile_grid::TileGrid;
+
+/// the rule a tile breaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// an edge or corner bit points at a neighbour which doesn't point back
+    UnreciprocatedEdge,
+    /// a corner bit is set without both of the edge bits next to it
+    DiagonalWithoutOrthogonals,
+    /// a bit is set while the centre of the tile is clear
+    BitWithCentreClear,
+    /// the tile isn't part of the tile set
+    NotInTileSet,
+}
+
+/// a single inconsistency found by [`validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub pt: Point,
+    /// the offending bit, `None` when the tile as a whole is at fault
+    pub bit: Option<usize>,
+    /// the neighbour the bit disagrees with, if any
+    pub neighbour: Option<Point>,
+    pub rule: Rule,
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "tile at ({}, {})", self.pt.x, self.pt.y)?;
+
+        if let Some(bit) = self.bit {
+            write!(f, ", bit {}", bit_name(bit))?;
+        }
+
+        if let Some(neighbour) = self.neighbour {
+            write!(f, ", neighbour ({}, {})", neighbour.x, neighbour.y)?;
+        }
+
+        write!(f, ": {:?}", self.rule)
+    }
+}
+
+pub fn bit_name(idx: usize) -> &'static str {
+    ["NW", "N", "NE", "W", "C", "E", "SW", "S", "SE"].get(idx).copied().unwrap_or("?")
+}
+
+/// for each corner, the two edges next to it
+const CORNER_EDGES: [(usize, [usize; 2]); 4] = [
+    (NW_IDX, [N_IDX, W_IDX]),
+    (NE_IDX, [N_IDX, E_IDX]),
+    (SW_IDX, [S_IDX, W_IDX]),
+    (SE_IDX, [S_IDX, E_IDX]),
+];
+
+/// for each bit, the offsets of the neighbours which have to point back at the tile and the bit
+/// they point with
+fn reciprocal_bits(bit: usize) -> &'static [((i32, i32), usize)] {
+    match bit {
+        N_IDX => &[((0, -1), S_IDX)],
+        W_IDX => &[((-1, 0), E_IDX)],
+        E_IDX => &[((1, 0), W_IDX)],
+        S_IDX => &[((0, 1), N_IDX)],
+        NW_IDX => &[((-1, -1), SE_IDX), ((0, -1), SW_IDX), ((-1, 0), NE_IDX)],
+        NE_IDX => &[((1, -1), SW_IDX), ((0, -1), SE_IDX), ((1, 0), NW_IDX)],
+        SW_IDX => &[((-1, 1), NE_IDX), ((0, 1), NW_IDX), ((-1, 0), SE_IDX)],
+        SE_IDX => &[((1, 1), NW_IDX), ((0, 1), NE_IDX), ((1, 0), SW_IDX)],
+        _ => &[],
+    }
+}
+
+/// lists every inconsistency in the grid without modifying it. neighbours outside of the grid
+/// are ignored, the same way stripping ignores them. an empty tile set skips the tile set check.
+pub fn validate<G: TileGrid>(grid: &G, tile_set: &[Tile3x3]) -> Vec<Issue> {
+    let tile_set: HashSet<Tile3x3> = tile_set.iter().copied().collect();
+    let mut issues = Vec::new();
+
+    for (pt, tile) in grid.iter_tiles() {
+        validate_tile(grid, &tile_set, &pt, &tile, &mut issues);
+    }
+
+    issues
+}
+
+/// like [`validate`], spreading bands of rows across threads for large grids. it's meant for
+/// dense grids, which yield every tile of their bounds row by row, so the issues are listed in
+/// the same order.
+pub fn validate_with<G: TileGrid + Sync>(grid: &G, tile_set: &[Tile3x3], config: &ParallelConfig) -> Vec<Issue> {
+    let bounds = grid.bounds();
+    let (w, h) = (bounds.w.max(0) as usize, bounds.h.max(0) as usize);
+    if config.is_serial(w * h) {
+        return validate(grid, tile_set);
+    }
+
+    let tile_set: HashSet<Tile3x3> = tile_set.iter().copied().collect();
+    let rows = config.chunk_rows(h);
+    let bands = (0..h).step_by(rows)
+        .map(|y| Rect::new(bounds.x, bounds.y + y as i32, bounds.w, rows.min(h - y) as i32))
+        .collect();
+
+    config.map(bands, |band| {
+        let mut issues = Vec::new();
+        for pt in band.points() {
+            if let Some(tile) = grid.tile_at(&pt) {
+                validate_tile(grid, &tile_set, &pt, &tile, &mut issues);
+            }
+        }
+        issues
+    }).concat()
+}
+
+fn validate_tile<G: TileGrid>(grid: &G, tile_set: &HashSet<Tile3x3>, pt: &Point, tile: &Tile3x3, issues: &mut Vec<Issue>) {
+    check_bits(grid, pt, tile, issues);
+
+    if !tile_set.is_empty() && !tile_set.contains(tile) {
+        issues.push(Issue { pt: *pt, bit: None, neighbour: None, rule: Rule::NotInTileSet });
+    }
+}
+
+fn check_bits<G: TileGrid>(grid: &G, pt: &Point, tile: &Tile3x3, issues: &mut Vec<Issue>) {
+    if !tile.get(C_IDX) {
+        for bit in NEIGHBOUR_IDXS.into_iter().filter(|bit| tile.get(*bit)) {
+            issues.push(Issue { pt: *pt, bit: Some(bit), neighbour: None, rule: Rule::BitWithCentreClear });
+        }
+        return;
+    }
+
+    for (corner, edges) in CORNER_EDGES {
+        if tile.get(corner) && !edges.iter().all(|edge| tile.get(*edge)) {
+            issues.push(Issue { pt: *pt, bit: Some(corner), neighbour: None, rule: Rule::DiagonalWithoutOrthogonals });
+        }
+    }
+
+    for bit in NEIGHBOUR_IDXS.into_iter().filter(|bit| tile.get(*bit)) {
+        for &((dx, dy), reciprocal) in reciprocal_bits(bit) {
+            let neighbour_pt = Point { x: pt.x + dx, y: pt.y + dy };
+            let Some(neighbour) = grid.tile_at(&neighbour_pt) else {
+                continue;
+            };
+
+            if !(neighbour.get(C_IDX) && neighbour.get(reciprocal)) {
+                issues.push(Issue { pt: *pt, bit: Some(bit), neighbour: Some(neighbour_pt), rule: Rule::UnreciprocatedEdge });
+            }
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use crate::grid::RectVec;
+use crate::tile_grid::TileGrid;
+use crate::point::Point;
+use crate::rect::Rect;
+use crate::tile::Tile3x3;
+
+pub const DEFAULT_CHUNK_SIZE: i32 = 64;
+
+/// an unbounded grid of tiles, split into square `RectVec` chunks which are allocated the first
+/// time a tile is written into them. chunks are keyed by chunk coordinate, the chunk at (1, 0)
+/// covers the world x coordinates `chunk_size..chunk_size * 2`.
+///
+/// tiles in chunks which haven't been allocated are empty, so stripping and painting treat the
+/// world as one continuous grid with no outside.
+#[derive(Clone)]
+pub struct ChunkedWorld {
+    chunk_size: i32,
+    chunks: HashMap<Point, RectVec>,
+}
+
+impl Default for ChunkedWorld {
+    fn default() -> Self {
+        Self::new(DEFAULT_CHUNK_SIZE)
+    }
+}
+
+impl ChunkedWorld {
+    pub fn new(chunk_size: i32) -> Self {
+        assert!(chunk_size > 0, "chunk size must be positive");
+
+        Self {
+            chunk_size,
+            chunks: HashMap::new(),
+        }
+    }
+
+    pub fn chunk_size(&self) -> i32 {
+        self.chunk_size
+    }
+
+    /// the coordinate of the chunk which contains the world point
+    pub fn chunk_coord(&self, pt: &Point) -> Point {
+        Point {
+            x: pt.x.div_euclid(self.chunk_size),
+            y: pt.y.div_euclid(self.chunk_size),
+        }
+    }
+
+    /// the world bounds covered by a chunk
+    pub fn chunk_bounds(&self, coord: &Point) -> Rect {
+        Rect::new(coord.x * self.chunk_size, coord.y * self.chunk_size, self.chunk_size, self.chunk_size)
+    }
+
+    pub fn chunk(&self, coord: &Point) -> Option<&RectVec> {
+        self.chunks.get(coord)
+    }
+
+    pub fn chunks(&self) -> impl Iterator<Item=(&Point, &RectVec)> {
+        self.chunks.iter()
+    }
+
+    /// the smallest rect containing every allocated chunk
+    pub fn allocated_bounds(&self) -> Option<Rect> {
+        let mut coords = self.chunks.keys();
+        let first = coords.next()?;
+
+        let (mut min, mut max) = (*first, *first);
+        for coord in coords {
+            min = Point { x: min.x.min(coord.x), y: min.y.min(coord.y) };
+            max = Point { x: max.x.max(coord.x), y: max.y.max(coord.y) };
+        }
+
+        Some(Rect::new(
+            min.x * self.chunk_size,
+            min.y * self.chunk_size,
+            (max.x - min.x + 1) * self.chunk_size,
+            (max.y - min.y + 1) * self.chunk_size,
+        ))
+    }
+
+    /// `None` if the chunk containing the point hasn't been allocated
+    pub fn get_pt(&self, pt: &Point) -> Option<&Tile3x3> {
+        self.chunks.get(&self.chunk_coord(pt))?.get_pt(pt)
+    }
+
+    /// allocates the chunk containing the point if needed. writing an empty tile never allocates.
+    pub fn set_pt(&mut self, pt: &Point, value: Tile3x3) {
+        let coord = self.chunk_coord(pt);
+
+        if let Some(chunk) = self.chunks.get_mut(&coord) {
+            chunk.set_pt(pt, value);
+            return;
+        }
+
+        if value.is_empty() {
+            return;
+        }
+
+        let mut chunk = RectVec::new(self.chunk_bounds(&coord));
+        chunk.set_pt(pt, value);
+        self.chunks.insert(coord, chunk);
+    }
+
+    /// every tile of every allocated chunk, chunks are visited in no particular order
+    pub fn iter_enumerate(&self) -> impl Iterator<Item=(Point, &Tile3x3)> {
+        self.chunks.values().flat_map(|chunk| chunk.iter_enumerate())
+    }
+}
+
+impl TileGrid for ChunkedWorld {
+    /// the allocated chunks, the world itself is unbounded
+    fn bounds(&self) -> Rect {
+        self.allocated_bounds().unwrap_or(Rect::new(0, 0, 0, 0))
+    }
+
+    fn tile_at(&self, pt: &Point) -> Option<Tile3x3> {
+        Some(self.get_pt(pt).copied().unwrap_or_default())
+    }
+
+    fn put_tile(&mut self, pt: &Point, tile: Tile3x3) {
+        self.set_pt(pt, tile)
+    }
+
+    fn empty_like(&self) -> Self {
+        ChunkedWorld::new(self.chunk_size)
+    }
+
+    fn iter_tiles(&self) -> impl Iterator<Item=(Point, Tile3x3)> + '_ {
+        self.iter_enumerate().map(|(pt, tile)| (pt, *tile))
+    }
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use autotiler::bitboard::BitBoard;
+use autotiler::grid::RectVec;
+use autotiler::matrix::Matrix;
+use autotiler::random;
+use autotiler::rect::Rect;
+use autotiler::strip::{StripContext, StripInto, StripStats};
+use common::every_tile;
+
+/// counts the allocations made by each thread, so the test harness doesn't get in the way
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations_during(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+fn assert_warm_passes_allocate_nothing<G: StripInto>(mut grid: G) {
+    let mut context = StripContext::default();
+
+    // the first run allocates the scratch grids
+    random::fill_random_with(&mut grid, &every_tile(), &mut random::seeded_rng(7));
+    context.strip_to_fixed_point(&mut grid);
+
+    random::fill_random_with(&mut grid, &every_tile(), &mut random::seeded_rng(8));
+    let mut stats = StripStats::default();
+
+    assert_eq!(allocations_during(|| {
+        stats = context.strip_to_fixed_point(&mut grid);
+        for _ in 0..4 {
+            context.strip(&mut grid);
+        }
+    }), 0);
+
+    // the grid still needed a few passes, so the spare grid was used as well
+    assert!(stats.iterations > 1);
+    assert!(stats.changed > 0);
+}
+
+#[test]
+fn warm_stripping_does_not_allocate() {
+    let bounds = Rect::new(-5, 3, 150, 90);
+
+    assert_warm_passes_allocate_nothing(RectVec::new(bounds.clone()));
+    assert_warm_passes_allocate_nothing(Matrix::new(bounds.clone()));
+    assert_warm_passes_allocate_nothing(BitBoard::new(bounds));
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use autotiler::autotile::auto_tile;
+use autotiler::random;
+use autotiler::tile::{minimal_3x3_tile_set, Tile3x3, C_IDX};
+use autotiler::validate::validate;
+use common::{every_tile, with_seed};
+
+#[test]
+fn auto_tiled_grid_is_valid_and_uses_the_tile_set() {
+    with_seed(|seed| {
+        let tile_set = minimal_3x3_tile_set();
+
+        let mut grid = vec![vec![Tile3x3::EMPTY; 23]; 17];
+        random::fill_random_with(&mut grid, &every_tile(), &mut random::seeded_rng(seed));
+
+        auto_tile(&tile_set, &mut grid);
+
+        assert_eq!(validate(&grid, &tile_set), vec![]);
+        assert!(grid.iter().flatten().all(|tile| tile_set.contains(tile)));
+    })
+}
+
+#[test]
+fn auto_tile_keeps_occupancy() {
+    with_seed(|seed| {
+        let tile_set = minimal_3x3_tile_set();
+
+        let mut grid = vec![vec![Tile3x3::EMPTY; 19]; 11];
+        random::fill_random_with(&mut grid, &every_tile(), &mut random::seeded_rng(seed));
+        let original = grid.clone();
+
+        auto_tile(&tile_set, &mut grid);
+
+        for (row, original_row) in grid.iter().zip(&original) {
+            for (tile, original_tile) in row.iter().zip(original_row) {
+                assert_eq!(tile.get(C_IDX), original_tile.get(C_IDX));
+            }
+        }
+    })
+}
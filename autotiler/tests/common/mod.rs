@@ -0,0 +1,61 @@
+// every test binary compiles its own copy of this module and only uses some of the helpers
+#![allow(dead_code)]
+
+use std::env::{self, VarError};
+use std::panic::{self, UnwindSafe};
+use autotiler::grid::RectVec;
+use autotiler::random;
+use autotiler::rect::Rect;
+use autotiler::tile::Tile3x3;
+use autotiler::tile_grid::TileGrid;
+use rand::{thread_rng, Rng};
+
+/// environment variable read by [`seed_from_env`] to replay a run
+pub const SEED_ENV_VAR: &str = "AUTOTILER_SEED";
+
+/// the seed in `AUTOTILER_SEED` if it is set, otherwise a fresh random seed.
+///
+/// panics if the variable is set to something other than a `u64`, rather than silently running
+/// with a seed that isn't the one asked for
+pub fn seed_from_env() -> u64 {
+    match env::var(SEED_ENV_VAR) {
+        Ok(seed) => seed.trim().parse().unwrap_or_else(|err| {
+            panic!("{SEED_ENV_VAR} must be a u64 seed, couldn't parse {seed:?}: {err}")
+        }),
+        Err(VarError::NotPresent) => thread_rng().gen(),
+        Err(VarError::NotUnicode(seed)) => panic!("{SEED_ENV_VAR} must be a u64 seed, found {seed:?}"),
+    }
+}
+
+/// runs the test with the seed from `AUTOTILER_SEED`, or a fresh one, and prints the seed if
+/// the test fails so the failure can be replayed
+pub fn with_seed(test: impl FnOnce(u64) + UnwindSafe) {
+    let seed = seed_from_env();
+
+    if let Err(err) = panic::catch_unwind(|| test(seed)) {
+        eprintln!("test failed with seed {seed}, replay it with {SEED_ENV_VAR}={seed}");
+        panic::resume_unwind(err);
+    }
+}
+
+/// all 512 tiles, including the ones which no stripped grid can hold
+pub fn every_tile() -> Vec<Tile3x3> {
+    (0..512).map(Tile3x3::from_bits).collect()
+}
+
+/// a grid of random tiles from `tile_set`, the same tiles for the same bounds and seed
+pub fn seeded_grid(bounds: Rect, tile_set: &[Tile3x3], seed: u64) -> RectVec {
+    let mut grid = RectVec::new(bounds);
+    random::fill_random_with(&mut grid, tile_set, &mut random::seeded_rng(seed));
+    grid
+}
+
+/// `actual` holds the same tiles as `expected`, and only empty tiles outside of it
+pub fn assert_same_tiles<G: TileGrid>(actual: &G, expected: &RectVec) {
+    for (pt, tile) in expected.iter_tiles() {
+        assert_eq!(actual.tile_at(&pt), Some(tile), "tile at {pt:?}");
+    }
+    for (pt, tile) in actual.iter_tiles() {
+        assert!(expected.bounds.contains(&pt) || tile.is_empty(), "tile at {pt:?} outside of the grid");
+    }
+}
@@ -0,0 +1,189 @@
+mod common;
+
+use autotiler::bitboard::BitBoard;
+use autotiler::grid::{grid_strip_invalid, grid_strip_invalid_fixed_point, grid_strip_invalid_with, RectVec};
+use autotiler::lookup::TileLookup;
+use autotiler::matrix::Matrix;
+use autotiler::parallel::ParallelConfig;
+use autotiler::pixel_matrix::PixelMatrix;
+use autotiler::point::Point;
+use autotiler::random;
+use autotiler::rect::Rect;
+use autotiler::sparse::{SparseGrid, TileStorage};
+use autotiler::tile::{minimal_3x3_tile_set, Tile3x3};
+use autotiler::tile_grid::TileGrid;
+use common::{assert_same_tiles, every_tile, seeded_grid, with_seed};
+use proptest::prelude::*;
+use rand::Rng;
+
+/// arbitrary bits, or only tiles from the tile set
+fn tiles(arbitrary: bool) -> Vec<Tile3x3> {
+    if arbitrary { every_tile() } else { minimal_3x3_tile_set() }
+}
+
+/// always threaded whatever the grid size or core count, in chunks of 7 rows
+const THREADED: ParallelConfig = ParallelConfig { chunk_rows: 7, serial_threshold: 0, max_threads: 4 };
+
+fn assert_strippers_agree(grid: &RectVec, config: &ParallelConfig) {
+    let expected = grid_strip_invalid(grid);
+    let actual = Matrix::from(grid).strip_invalid_with(config);
+
+    assert_eq!(actual.tile_bounds, expected.bounds);
+    assert!(expected.iter_tiles().eq(actual.iter_tiles()));
+    assert!(expected.iter_tiles().eq(grid_strip_invalid_with(grid, config).iter_tiles()));
+
+    let pixels = PixelMatrix::from(&Matrix::from(grid)).strip_invalid();
+    assert!(expected.iter_tiles().eq(pixels.iter_tiles()));
+
+    let board = BitBoard::from(grid).strip_invalid();
+    assert!(expected.iter_tiles().eq(board.iter_tiles()));
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(2000))]
+
+    #[test]
+    fn conversions_round_trip(x in -8..8, y in -8..8, w in 0..40, h in 0..40, seed: u64, arbitrary: bool) {
+        let grid = seeded_grid(Rect::new(x, y, w, h), &tiles(arbitrary), seed);
+        let matrix = Matrix::from(&grid);
+
+        prop_assert!(grid.iter_tiles().eq(matrix.iter_tiles()));
+        prop_assert!(grid.iter_tiles().eq(RectVec::from(&matrix).iter_tiles()));
+
+        let pixels = PixelMatrix::from(&matrix);
+        prop_assert!(grid.iter_tiles().eq(pixels.iter_tiles()));
+        prop_assert_eq!(&Matrix::from(&pixels).data, &matrix.data);
+
+        let board = BitBoard::from(&grid);
+        prop_assert!(grid.iter_tiles().eq(board.iter_tiles()));
+        prop_assert!(grid.iter_tiles().eq(RectVec::from(&board).iter_tiles()));
+    }
+
+    #[test]
+    fn pixel_views_agree(x in -8..8, y in -8..8, w in 1..20, h in 1..20, seed: u64) {
+        let grid = seeded_grid(Rect::new(x, y, w, h), &every_tile(), seed);
+        let matrix = Matrix::from(&grid);
+        let pixels = PixelMatrix::from(&matrix);
+
+        for (pt, tile) in grid.iter_tiles() {
+            for bit in 0..9 {
+                let px = Point { x: pt.x * 3 + bit as i32 % 3, y: pt.y * 3 + bit as i32 / 3 };
+                prop_assert_eq!(matrix.get_pt(&px), Some(tile.get(bit)));
+                prop_assert_eq!(pixels.get_pt(&px), Some(tile.get(bit)));
+            }
+        }
+
+        prop_assert!(matrix.iter_enumerate().all(|(px, bit)| pixels.get_pt(&px) == Some(*bit)));
+    }
+
+    /// re-stripping only around the painted tiles of a valid grid gives the same grid as
+    /// stripping all of it to a fixed point
+    #[test]
+    fn dirty_stripping_matches_the_fixed_point(
+        w in 1..40,
+        h in 1..40,
+        seed: u64,
+        paints in prop::collection::vec((0..40, 0..40, 0..512u16), 1..20),
+    ) {
+        let (mut grid, _) = grid_strip_invalid_fixed_point(&seeded_grid(Rect::new(0, 0, w, h), &every_tile(), seed));
+        let mut points = Vec::new();
+
+        for (x, y, bits) in paints {
+            let pt = Point { x: x % w, y: y % h };
+            grid.set_pt(&pt, Tile3x3::from_bits(bits));
+            points.push(pt);
+        }
+
+        let painted = grid.clone();
+        let (expected, _) = grid_strip_invalid_fixed_point(&painted);
+        let mut changed = grid.strip_invalid_points(&points);
+
+        prop_assert!(expected.iter_tiles().eq(grid.iter_tiles()));
+
+        // exactly the tiles which differ from the painted grid are reported
+        let mut differ: Vec<Point> = painted.iter_tiles()
+            .filter(|(pt, tile)| grid.tile_at(pt) != Some(*tile))
+            .map(|(pt, _)| pt)
+            .collect();
+        changed.sort_by_key(|pt| (pt.y, pt.x));
+        differ.sort_by_key(|pt| (pt.y, pt.x));
+        prop_assert_eq!(changed, differ);
+    }
+
+    #[test]
+    fn strippers_agree_on_serial_grids(x in -8..8, y in -8..8, w in 1..64, h in 1..64, seed: u64, arbitrary: bool) {
+        assert_strippers_agree(&seeded_grid(Rect::new(x, y, w, h), &tiles(arbitrary), seed), &ParallelConfig::serial());
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(200))]
+
+    #[test]
+    fn strippers_agree_on_threaded_grids(
+        x in -8..8,
+        y in -8..8,
+        w in 1..100,
+        h in (1..130usize).prop_filter("a partial last chunk", |h| h % THREADED.chunk_rows != 0),
+        seed: u64,
+        arbitrary: bool,
+    ) {
+        assert_strippers_agree(&seeded_grid(Rect::new(x, y, w, h as i32), &tiles(arbitrary), seed), &THREADED);
+    }
+}
+
+#[test]
+fn strippers_agree_on_short_last_chunks() {
+    // odd heights in chunks of two rows, so the last chunk is a single row
+    let config = ParallelConfig { chunk_rows: 2, ..THREADED };
+
+    for (w, h) in [(128, 33), (4096, 1), (2048, 3), (65, 65), (64, 97)] {
+        for seed in 0..4 {
+            assert_strippers_agree(&seeded_grid(Rect::new(0, 0, w, h), &every_tile(), seed), &config);
+        }
+    }
+}
+
+/// sparse and dense storage strip, strip to a fixed point and solve exactly like `RectVec`
+fn assert_storages_agree(grid: &RectVec, lookup: &TileLookup) {
+    let stripped = grid.strip_invalid();
+    let (fixed, stats) = grid.strip_invalid_fixed_point();
+    let solved = grid.solve(lookup);
+
+    let sparse = SparseGrid::from(grid);
+    assert_same_tiles(&sparse.strip_invalid(), &stripped);
+    let (sparse_fixed, sparse_stats) = sparse.strip_invalid_fixed_point();
+    assert_same_tiles(&sparse_fixed, &fixed);
+    assert_eq!(sparse_stats, stats);
+    assert_same_tiles(&sparse.solve(lookup), &solved);
+
+    for storage in [TileStorage::Dense(grid.clone()), TileStorage::Sparse(sparse.clone())] {
+        assert_same_tiles(&storage.strip_invalid(), &stripped);
+        let (storage_fixed, storage_stats) = storage.strip_invalid_fixed_point();
+        assert_same_tiles(&storage_fixed, &fixed);
+        assert_eq!(storage_stats, stats);
+        assert_same_tiles(&storage.solve(lookup), &solved);
+    }
+}
+
+#[test]
+fn sparse_storage_agrees_with_dense_grids() {
+    with_seed(|seed| {
+        let lookup = TileLookup::new(&minimal_3x3_tile_set());
+        let mut rng = random::seeded_rng(seed);
+
+        for bounds in [Rect::new(0, 0, 24, 17), Rect::new(-9, 4, 31, 12), Rect::new(6, -13, 8, 27)] {
+            // every tile filled, and then only around one in twenty
+            for filled in [1.0, 0.05] {
+                let mut grid = seeded_grid(bounds.clone(), &every_tile(), rng.gen());
+                for pt in bounds.points() {
+                    if !rng.gen_bool(filled) {
+                        grid.set_pt(&pt, Tile3x3::EMPTY);
+                    }
+                }
+
+                assert_storages_agree(&grid, &lookup);
+            }
+        }
+    })
+}
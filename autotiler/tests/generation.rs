@@ -0,0 +1,153 @@
+mod common;
+
+use autotiler::generate::{
+    cellular_caves, drunkard_walk, grid_from_gray_image, grid_from_mask, mask_from_grid, matrix_from_gray_image,
+    matrix_from_mask, noise, rooms_and_corridors, MaskSizeError, Occupancy,
+};
+use autotiler::grid::generate_test_grid_seeded;
+use autotiler::matrix::generate_random_matrix_seeded;
+use autotiler::random;
+use autotiler::tile::minimal_3x3_tile_set;
+use autotiler::tile_grid::TileGrid;
+use common::with_seed;
+use image::{GrayImage, Luma};
+
+#[test]
+fn same_seed_generates_same_grid() {
+    with_seed(|seed| {
+        let tile_set = minimal_3x3_tile_set();
+
+        let a = generate_test_grid_seeded(&tile_set, 37, 21, seed);
+        let b = generate_test_grid_seeded(&tile_set, 37, 21, seed);
+
+        assert!(a.iter_tiles().eq(b.iter_tiles()));
+    })
+}
+
+#[test]
+fn same_seed_generates_same_tiles_for_grid_and_matrix() {
+    with_seed(|seed| {
+        let tile_set = minimal_3x3_tile_set();
+
+        let grid = generate_test_grid_seeded(&tile_set, 37, 21, seed);
+        let matrix = generate_random_matrix_seeded(&tile_set, 37, 21, seed);
+
+        assert!(grid.iter_tiles().eq(matrix.iter_tiles()));
+    })
+}
+
+#[test]
+fn different_seeds_generate_different_grids() {
+    with_seed(|seed| {
+        let tile_set = minimal_3x3_tile_set();
+
+        let a = generate_test_grid_seeded(&tile_set, 32, 32, seed);
+        let b = generate_test_grid_seeded(&tile_set, 32, 32, seed.wrapping_add(1));
+
+        assert!(!a.iter_tiles().eq(b.iter_tiles()));
+    })
+}
+
+/// every generator, with the same seed
+fn generated_layers(seed: u64) -> [(&'static str, Occupancy); 4] {
+    let mut rng = random::seeded_rng(seed);
+
+    [
+        ("noise", noise(47, 33, 0.45, &mut rng)),
+        ("cellular caves", cellular_caves(47, 33, 0.45, 4, &mut rng)),
+        ("rooms and corridors", rooms_and_corridors(47, 33, 6, 3, 9, &mut rng)),
+        ("drunkard walk", drunkard_walk(47, 33, 0.4, &mut rng)),
+    ]
+}
+
+#[test]
+fn generated_layers_tile_into_valid_grids() {
+    with_seed(|seed| {
+        let tile_set = minimal_3x3_tile_set();
+
+        for (name, occupancy) in generated_layers(seed) {
+            let grid = occupancy.to_grid();
+
+            assert!(occupancy.count() > 0, "{name} occupied nothing");
+            assert_eq!(grid.validate(&tile_set), vec![], "{name}");
+            assert_eq!(occupancy.to_matrix().validate(&tile_set), vec![], "{name}");
+            assert_eq!(Occupancy::from_grid(&grid), occupancy, "{name}");
+        }
+    })
+}
+
+#[test]
+fn noise_density_is_roughly_right() {
+    with_seed(|seed| {
+        let mut rng = random::seeded_rng(seed);
+        let cells = 100 * 100;
+
+        assert_eq!(noise(100, 100, 0.0, &mut rng).count(), 0);
+        assert_eq!(noise(100, 100, 1.0, &mut rng).count(), cells);
+
+        // ten thousand cells put 0.45..0.55 about ten standard deviations away from the mean
+        let half = noise(100, 100, 0.5, &mut rng).count() as f64 / cells as f64;
+        assert!((0.45..0.55).contains(&half), "density of {half}");
+
+        // out of range densities are clamped, NaN occupies nothing
+        assert_eq!(noise(100, 100, -1.0, &mut rng).count(), 0);
+        assert_eq!(noise(100, 100, 2.0, &mut rng).count(), cells);
+        assert_eq!(noise(100, 100, f64::NAN, &mut rng).count(), 0);
+        assert_eq!(drunkard_walk(100, 100, f64::NAN, &mut rng).count(), 0);
+    })
+}
+
+#[test]
+fn masks_round_trip_through_grids() {
+    with_seed(|seed| {
+        let (width, height) = (29, 17);
+        let mask = noise(width, height, 0.5, &mut random::seeded_rng(seed)).data;
+
+        let grid = grid_from_mask(&mask, width, height).unwrap();
+        let matrix = matrix_from_mask(&mask, width, height).unwrap();
+
+        assert_eq!(mask_from_grid(&grid), mask);
+        assert_eq!(mask_from_grid(&matrix), mask);
+        assert!(grid.iter_tiles().eq(matrix.iter_tiles()));
+    })
+}
+
+#[test]
+fn masks_of_the_wrong_size_are_rejected() {
+    let error = MaskSizeError { width: 4, height: 3, len: 11 };
+
+    assert_eq!(Occupancy::from_mask(4, 3, vec![true; 11]), Err(error.clone()));
+    assert_eq!(grid_from_mask(&[true; 11], 4, 3).err(), Some(error.clone()));
+    assert_eq!(matrix_from_mask(&[true; 11], 4, 3).err(), Some(error.clone()));
+    assert_eq!(error.to_string(), "a 4x3 mask needs 12 cells, found 11");
+
+    assert!(Occupancy::from_mask(u32::MAX, u32::MAX, vec![]).is_err());
+    assert!(Occupancy::from_mask(0, 7, vec![]).is_ok());
+}
+
+#[test]
+fn huge_sizes_dont_overflow() {
+    let mut rng = random::seeded_rng(5);
+
+    // a layer too small for any room gives up at once, instead of counting the attempts in u32
+    assert_eq!(rooms_and_corridors(0, 9, u32::MAX, 1, 3, &mut rng).count(), 0);
+    assert_eq!(Occupancy::new(u32::MAX, 0).data.len(), 0);
+}
+
+#[test]
+fn gray_images_are_occupied_at_the_threshold() {
+    let img = GrayImage::from_fn(5, 1, |x, _| Luma([[0, 99, 100, 101, 255][x as usize]]));
+    let expected = vec![false, false, true, true, true];
+
+    assert_eq!(Occupancy::from_gray_image(&img, 100).data, expected);
+    assert_eq!(mask_from_grid(&grid_from_gray_image(&img, 100)), expected);
+    assert_eq!(mask_from_grid(&matrix_from_gray_image(&img, 100)), expected);
+    assert!(grid_from_gray_image(&img, 100).iter_tiles().eq(matrix_from_gray_image(&img, 100).iter_tiles()));
+
+    // a threshold of 0 occupies every pixel
+    assert_eq!(Occupancy::from_gray_image(&img, 0).count(), 5);
+    assert_eq!(Occupancy::from_gray_image(&img, 255).count(), 1);
+
+    let occupancy = Occupancy::from_gray_image(&img, 100);
+    assert_eq!(Occupancy::from_gray_image(&occupancy.to_gray_image(), 255), occupancy);
+}
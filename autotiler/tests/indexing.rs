@@ -0,0 +1,98 @@
+use autotiler::grid::RectVec;
+use autotiler::matrix::Matrix;
+use autotiler::point::Point;
+use autotiler::rect::Rect;
+use autotiler::tile::Tile3x3;
+
+/// bounds with negative, positive and mixed origins
+fn bounds() -> [Rect; 4] {
+    [Rect::new(-7, -4, 5, 3), Rect::new(3, 9, 4, 6), Rect::new(-2, 5, 3, 1), Rect::new(0, 0, 2, 2)]
+}
+
+/// the points right next to each edge of `rect`, one step outside of it
+fn just_outside(rect: &Rect) -> Vec<Point> {
+    let (x, y, right, bottom) = (rect.x, rect.y, rect.x + rect.w, rect.y + rect.h);
+    let mut points = Vec::new();
+
+    for px in x - 1..=right {
+        points.push(Point { x: px, y: y - 1 });
+        points.push(Point { x: px, y: bottom });
+    }
+    for py in y..bottom {
+        points.push(Point { x: x - 1, y: py });
+        points.push(Point { x: right, y: py });
+    }
+
+    points
+}
+
+/// a different tile for every point of the bounds
+fn tile_for(rect: &Rect, pt: &Point) -> Tile3x3 {
+    Tile3x3::from_bits(((pt.y - rect.y) * rect.w + pt.x - rect.x) as u16 + 1)
+}
+
+#[test]
+fn rect_vec_indexes_relative_to_its_origin() {
+    for rect in bounds() {
+        let mut grid = RectVec::new(rect.clone());
+
+        for (i, pt) in rect.points().enumerate() {
+            assert_eq!(grid.idx(&pt), Some(i), "{pt:?} in {rect:?}");
+            grid.set_pt(&pt, tile_for(&rect, &pt));
+        }
+
+        for pt in rect.points() {
+            assert_eq!(grid.get_pt(&pt), Some(&tile_for(&rect, &pt)));
+        }
+
+        let before: Vec<_> = rect.points().map(|pt| *grid.get_pt(&pt).unwrap()).collect();
+        for pt in just_outside(&rect) {
+            assert_eq!(grid.idx(&pt), None, "{pt:?} outside of {rect:?}");
+            assert_eq!(grid.get_pt(&pt), None);
+            grid.set_pt(&pt, Tile3x3::FULL);
+        }
+
+        let after: Vec<_> = rect.points().map(|pt| *grid.get_pt(&pt).unwrap()).collect();
+        assert_eq!(before, after, "writes outside of {rect:?} landed inside of it");
+    }
+}
+
+#[test]
+fn matrix_indexes_relative_to_its_origin() {
+    for rect in bounds() {
+        let mut matrix = Matrix::new(rect.clone());
+
+        for (i, pt) in rect.points().enumerate() {
+            assert_eq!(matrix.idx_tile(&pt), Some(i * 9), "{pt:?} in {rect:?}");
+            matrix.set_tile(&pt, &tile_for(&rect, &pt));
+        }
+
+        for pt in rect.points() {
+            let tile = tile_for(&rect, &pt);
+            assert_eq!(matrix.get_tile(&pt), Some(tile));
+
+            // every pixel of the tile is found at its pixel coordinate
+            for bit in 0..9 {
+                let px = Point { x: pt.x * 3 + bit as i32 % 3, y: pt.y * 3 + bit as i32 / 3 };
+                assert_eq!(matrix.idx(&px), Some(matrix.idx_tile(&pt).unwrap() + bit));
+                assert_eq!(matrix.get_pt(&px), Some(tile.get(bit)));
+            }
+        }
+
+        let before = matrix.data.clone();
+        for pt in just_outside(&rect) {
+            assert_eq!(matrix.idx_tile(&pt), None, "{pt:?} outside of {rect:?}");
+            assert_eq!(matrix.get_tile(&pt), None);
+            matrix.set_tile(&pt, &Tile3x3::FULL);
+        }
+
+        let px_rect = Rect::new(rect.x * 3, rect.y * 3, rect.w * 3, rect.h * 3);
+        for px in just_outside(&px_rect) {
+            assert_eq!(matrix.idx(&px), None, "pixel {px:?} outside of {px_rect:?}");
+            assert_eq!(matrix.get_pt(&px), None);
+            matrix.set_pt(&px, true);
+        }
+
+        assert_eq!(matrix.data, before, "writes outside of {rect:?} landed inside of it");
+    }
+}
@@ -0,0 +1,66 @@
+use autotiler::loader::{load_tile_set, load_tile_set_from_bytes, tile_set_from_image, TileSetError, TileSetLayout};
+use autotiler::tile::minimal_3x3_tile_set;
+use image::{DynamicImage, RgbImage, RgbaImage};
+
+const MINIMAL_PNG: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../img/3x3-minimal.png");
+
+#[test]
+fn bundled_image_is_the_minimal_tile_set() {
+    let layout = TileSetLayout::minimal_3x3();
+
+    assert_eq!(load_tile_set(MINIMAL_PNG, &layout).unwrap(), minimal_3x3_tile_set());
+
+    let bytes = std::fs::read(MINIMAL_PNG).unwrap();
+    assert_eq!(load_tile_set_from_bytes(&bytes, &layout).unwrap(), minimal_3x3_tile_set());
+}
+
+#[test]
+fn non_rgba_image_is_rejected() {
+    let img = DynamicImage::ImageRgb8(RgbImage::new(768, 256));
+
+    let result = tile_set_from_image(&img, &TileSetLayout::minimal_3x3());
+    assert!(matches!(result, Err(TileSetError::NotRgba(image::ColorType::Rgb8))));
+}
+
+#[test]
+fn small_image_is_out_of_range() {
+    let img = DynamicImage::ImageRgba8(RgbaImage::new(64, 64));
+
+    let result = tile_set_from_image(&img, &TileSetLayout::minimal_3x3());
+    assert!(matches!(result, Err(TileSetError::SampleOutOfRange { width: 64, height: 64, .. })));
+}
+
+#[test]
+fn missing_file_names_the_path() {
+    let result = load_tile_set("does/not/exist.png", &TileSetLayout::minimal_3x3());
+    assert!(matches!(&result, Err(TileSetError::MissingFile { path, .. }) if path.ends_with("exist.png")));
+}
+
+#[test]
+fn corrupt_bytes_fail_to_decode() {
+    let layout = TileSetLayout::minimal_3x3();
+
+    let garbage = load_tile_set_from_bytes(b"definitely not an image", &layout);
+    assert!(matches!(garbage, Err(TileSetError::Decode(_))));
+
+    // a real png cut off part way through its pixel data
+    let bytes = std::fs::read(MINIMAL_PNG).unwrap();
+    let truncated = load_tile_set_from_bytes(&bytes[..bytes.len() / 2], &layout);
+    assert!(matches!(truncated, Err(TileSetError::Decode(_))));
+}
+
+#[test]
+fn huge_layouts_overflow_instead_of_wrapping() {
+    let img = DynamicImage::ImageRgba8(RgbaImage::new(64, 64));
+    let huge = |layout: TileSetLayout| tile_set_from_image(&img, &layout);
+
+    let spaced = TileSetLayout { spacing: u32::MAX, ..TileSetLayout::minimal_3x3() };
+    assert!(matches!(huge(spaced), Err(TileSetError::LayoutOverflow { row: 0, col: 0 })));
+
+    let margin = TileSetLayout { margin: u32::MAX - 5, ..TileSetLayout::minimal_3x3() };
+    assert!(matches!(huge(margin), Err(TileSetError::LayoutOverflow { row: 0, col: 0 })));
+
+    // past the image, but still a valid coordinate
+    let wide = TileSetLayout { spacing: u32::MAX / 4, rows: 1, columns: 2, ..TileSetLayout::minimal_3x3() };
+    assert!(matches!(huge(wide), Err(TileSetError::SampleOutOfRange { .. })));
+}
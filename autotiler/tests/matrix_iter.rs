@@ -0,0 +1,92 @@
+mod common;
+
+use autotiler::matrix::Matrix;
+use autotiler::point::Point;
+use autotiler::random;
+use autotiler::rect::Rect;
+use autotiler::tile::Tile3x3;
+use autotiler::tile_grid::TileGrid;
+use common::every_tile;
+use proptest::prelude::*;
+
+fn seeded_matrix(bounds: Rect, seed: u64) -> Matrix {
+    let mut matrix = Matrix::new(bounds);
+    random::fill_random_with(&mut matrix, &every_tile(), &mut random::seeded_rng(seed));
+    matrix
+}
+
+fn to_tile(tile: &[bool]) -> Tile3x3 {
+    Tile3x3::from_array(tile.try_into().unwrap())
+}
+
+proptest! {
+    #[test]
+    fn tiles_are_yielded_once(x in -8..8, y in -8..8, w in 0..20, h in 0..20, seed: u64) {
+        let matrix = seeded_matrix(Rect::new(x, y, w, h), seed);
+
+        let tiles: Vec<(Point, Tile3x3)> = matrix.iter_tiles_enumerate().map(|(pt, tile)| (pt, to_tile(tile))).collect();
+        prop_assert_eq!(tiles.len(), (w * h) as usize);
+        prop_assert!(tiles.into_iter().eq(matrix.bounds().points().map(|pt| (pt, matrix.get_tile(&pt).unwrap()))));
+    }
+
+    #[test]
+    fn mutable_tiles_write_through(x in -8..8, y in -8..8, w in 0..20, h in 0..20, seed: u64) {
+        let mut matrix = seeded_matrix(Rect::new(x, y, w, h), seed);
+
+        for (pt, tile) in matrix.iter_tiles_enumerate_mut() {
+            tile.copy_from_slice(&Tile3x3::from_bits((pt.x * 31 + pt.y) as u16 & Tile3x3::MASK).to_array());
+        }
+
+        for (pt, tile) in matrix.iter_tiles() {
+            prop_assert_eq!(tile, Tile3x3::from_bits((pt.x * 31 + pt.y) as u16 & Tile3x3::MASK));
+        }
+    }
+
+    #[test]
+    fn row_chunks_cover_every_tile(x in -8..8, y in -8..8, w in 1..20, h in 1..40, rows in 1..12, seed: u64) {
+        let mut matrix = seeded_matrix(Rect::new(x, y, w, h), seed);
+        let expected: Vec<(Point, Tile3x3)> = matrix.iter_tiles().collect();
+
+        let bands: Vec<Rect> = matrix.row_chunks(rows).map(|band| band.bounds).collect();
+        prop_assert_eq!(bands.len() as i32, (h + rows - 1) / rows);
+        prop_assert!(bands.iter().all(|band| band.x == x && band.w == w && band.h <= rows));
+
+        let tiles: Vec<(Point, Tile3x3)> = matrix.row_chunks(rows)
+            .flat_map(|band| band.iter_tiles_enumerate().map(|(pt, tile)| (pt, to_tile(tile))).collect::<Vec<_>>())
+            .collect();
+        prop_assert_eq!(&tiles, &expected);
+
+        for mut band in matrix.row_chunks_mut(rows) {
+            for (_, tile) in band.iter_tiles_enumerate_mut() {
+                tile.fill(false);
+            }
+        }
+        prop_assert!(matrix.data.iter().all(|bit| !bit));
+    }
+
+    #[test]
+    fn regions_are_clipped_to_the_matrix(
+        x in -8..8, y in -8..8, w in 0..20, h in 0..20,
+        rx in -30..30, ry in -30..30, rw in 0..30, rh in 0..30,
+        seed: u64,
+    ) {
+        let mut matrix = seeded_matrix(Rect::new(x, y, w, h), seed);
+        let region = Rect::new(rx, ry, rw, rh);
+
+        let expected: Vec<(Point, Tile3x3)> = region.points()
+            .filter_map(|pt| Some((pt, matrix.get_tile(&pt)?)))
+            .collect();
+        let tiles: Vec<(Point, Tile3x3)> = matrix.iter_region(&region).map(|(pt, tile)| (pt, to_tile(tile))).collect();
+        prop_assert_eq!(&tiles, &expected);
+
+        let original = matrix.clone();
+        for (_, tile) in matrix.iter_region_mut(&region) {
+            tile.fill(false);
+        }
+
+        for (pt, tile) in matrix.iter_tiles() {
+            let expected = if region.contains(&pt) { Tile3x3::EMPTY } else { original.get_tile(&pt).unwrap() };
+            prop_assert_eq!(tile, expected);
+        }
+    }
+}
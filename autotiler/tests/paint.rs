@@ -0,0 +1,101 @@
+mod common;
+
+use autotiler::grid::{generate_test_grid_seeded, grid_strip_invalid_fixed_point, RectVec};
+use autotiler::point::Point;
+use autotiler::random;
+use autotiler::tile::{minimal_3x3_tile_set, Tile3x3, C_IDX};
+use autotiler::tile_grid::TileGrid;
+use common::{every_tile, with_seed};
+use rand::Rng;
+
+/// a valid grid with a mix of joined and unjoined tiles
+fn valid_grid(seed: u64) -> RectVec {
+    grid_strip_invalid_fixed_point(&generate_test_grid_seeded(&every_tile(), 24, 18, seed)).0
+}
+
+/// every tile which isn't in `touched` is the same in both grids
+fn assert_untouched(before: &RectVec, after: &RectVec, touched: &[Point]) {
+    for (pt, tile) in before.iter_tiles() {
+        if !touched.contains(&pt) {
+            assert_eq!(after.tile_at(&pt), Some(tile), "tile at {pt:?} changed");
+        }
+    }
+}
+
+fn random_point(rng: &mut impl Rng) -> Point {
+    // a little outside of the grid, so clipping is covered too
+    Point { x: rng.gen_range(-2..26), y: rng.gen_range(-2..20) }
+}
+
+#[test]
+fn paint_path_keeps_the_grid_valid() {
+    with_seed(|seed| {
+        let tile_set = minimal_3x3_tile_set();
+        let mut rng = random::seeded_rng(seed);
+        let mut grid = valid_grid(seed);
+
+        for _ in 0..20 {
+            let (from, to) = (random_point(&mut rng), random_point(&mut rng));
+            let before = grid.clone();
+
+            grid.paint_path(from, to);
+
+            assert_eq!(grid.validate(&tile_set), vec![]);
+            assert_untouched(&before, &grid, &autotiler::paint::path_points(from, to));
+        }
+    })
+}
+
+/// the 3x3 block of tiles around `pt`
+fn neighbourhood(pt: Point) -> Vec<Point> {
+    (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| Point { x: pt.x + dx, y: pt.y + dy })).collect()
+}
+
+#[test]
+fn paint_fill_keeps_the_grid_valid() {
+    with_seed(|seed| {
+        let tile_set = minimal_3x3_tile_set();
+        let mut rng = random::seeded_rng(seed);
+        let mut grid = valid_grid(seed);
+
+        for _ in 0..50 {
+            let pt = random_point(&mut rng);
+            let before = grid.clone();
+
+            grid.paint_fill(pt);
+
+            assert_eq!(grid.validate(&tile_set), vec![]);
+            assert_untouched(&before, &grid, &neighbourhood(pt));
+            if let Some(tile) = grid.tile_at(&pt) {
+                assert!(tile.get(C_IDX));
+            }
+        }
+    })
+}
+
+#[test]
+fn erase_keeps_the_grid_valid() {
+    with_seed(|seed| {
+        let tile_set = minimal_3x3_tile_set();
+        let mut rng = random::seeded_rng(seed);
+        let mut grid = valid_grid(seed);
+
+        // fill some first, so there's something to erase
+        for _ in 0..50 {
+            grid.paint_fill(random_point(&mut rng));
+        }
+
+        for _ in 0..50 {
+            let pt = random_point(&mut rng);
+            let before = grid.clone();
+
+            grid.erase(pt);
+
+            assert_eq!(grid.validate(&tile_set), vec![]);
+            assert_untouched(&before, &grid, &neighbourhood(pt));
+            if let Some(tile) = grid.tile_at(&pt) {
+                assert_eq!(tile, Tile3x3::EMPTY);
+            }
+        }
+    })
+}
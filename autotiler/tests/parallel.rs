@@ -0,0 +1,104 @@
+mod common;
+
+use autotiler::grid::{grid_solve_with, grid_strip_invalid_with, RectVec};
+use autotiler::lookup::TileLookup;
+use autotiler::matrix::Matrix;
+use autotiler::parallel::ParallelConfig;
+use autotiler::random;
+use autotiler::rect::Rect;
+use autotiler::tile::minimal_3x3_tile_set;
+use autotiler::tile_grid::TileGrid;
+use autotiler::validate::{validate, validate_with};
+use common::{every_tile, seeded_grid, with_seed};
+
+/// configurations which always run in parallel, with fixed and automatic chunk sizes
+fn parallel_configs() -> [ParallelConfig; 3] {
+    [
+        ParallelConfig { chunk_rows: 3, serial_threshold: 0, max_threads: 4 },
+        ParallelConfig { chunk_rows: 0, serial_threshold: 0, max_threads: 3 },
+        ParallelConfig { chunk_rows: 1, serial_threshold: 0, max_threads: 2 },
+    ]
+}
+
+#[test]
+fn parallel_stripping_matches_serial() {
+    with_seed(|seed| {
+        let grid = seeded_grid(Rect::new(-3, 5, 37, 50), &every_tile(), seed);
+        let matrix = Matrix::from(&grid);
+
+        let expected = grid_strip_invalid_with(&grid, &ParallelConfig::serial());
+        assert!(expected.iter_tiles().eq(matrix.strip_invalid_with(&ParallelConfig::serial()).iter_tiles()));
+
+        for config in parallel_configs() {
+            assert!(expected.iter_tiles().eq(grid_strip_invalid_with(&grid, &config).iter_tiles()));
+            assert!(expected.iter_tiles().eq(matrix.strip_invalid_with(&config).iter_tiles()));
+        }
+    })
+}
+
+#[test]
+fn parallel_solving_matches_serial() {
+    with_seed(|seed| {
+        let lookup = TileLookup::new(&minimal_3x3_tile_set());
+        let grid = seeded_grid(Rect::new(4, -2, 41, 29), &every_tile(), seed);
+        let matrix = Matrix::from(&grid);
+
+        let expected = grid_solve_with(&grid, &lookup, &ParallelConfig::serial());
+        assert!(expected.iter_tiles().eq(matrix.solve_with(&lookup, &ParallelConfig::serial()).iter_tiles()));
+
+        for config in parallel_configs() {
+            assert!(expected.iter_tiles().eq(grid_solve_with(&grid, &lookup, &config).iter_tiles()));
+            assert!(expected.iter_tiles().eq(matrix.solve_with(&lookup, &config).iter_tiles()));
+        }
+    })
+}
+
+#[test]
+fn parallel_validation_matches_serial() {
+    with_seed(|seed| {
+        let tile_set = minimal_3x3_tile_set();
+        let grid = seeded_grid(Rect::new(0, 0, 33, 47), &every_tile(), seed);
+        let expected = validate(&grid, &tile_set);
+
+        assert!(!expected.is_empty());
+        for config in parallel_configs() {
+            assert_eq!(validate_with(&grid, &tile_set, &config), expected);
+            assert_eq!(validate_with(&Matrix::from(&grid), &tile_set, &config), expected);
+        }
+    })
+}
+
+#[test]
+fn parallel_generation_matches_serial() {
+    with_seed(|seed| {
+        let tile_set = minimal_3x3_tile_set();
+        let bounds = Rect::new(-7, -7, 30, 45);
+
+        let mut expected = RectVec::new(bounds.clone());
+        random::fill_random_rows(&mut expected, &tile_set, seed, &ParallelConfig::serial());
+
+        for config in parallel_configs() {
+            let mut grid = RectVec::new(bounds.clone());
+            random::fill_random_rows(&mut grid, &tile_set, seed, &config);
+            assert!(expected.iter_tiles().eq(grid.iter_tiles()));
+
+            let mut matrix = Matrix::new(bounds.clone());
+            random::fill_random_rows(&mut matrix, &tile_set, seed, &config);
+            assert!(expected.iter_tiles().eq(matrix.iter_tiles()));
+        }
+    })
+}
+
+#[test]
+fn thread_count_is_capped() {
+    let config = ParallelConfig { max_threads: 2, ..ParallelConfig::default() };
+    assert_eq!(config.threads(), 2);
+
+    let threads = config.map((0..64).collect(), |_| std::thread::current().id());
+    let mut distinct: Vec<_> = threads.into_iter().collect();
+    distinct.sort_by_key(|id| format!("{id:?}"));
+    distinct.dedup();
+    assert!(distinct.len() <= 2);
+
+    assert!(ParallelConfig::serial().is_serial(usize::MAX - 1));
+}
@@ -0,0 +1,15 @@
+mod common;
+
+use std::env;
+use common::{seed_from_env, SEED_ENV_VAR};
+
+// the only test in this binary, so no other test reads the variable while it is changed
+#[test]
+#[should_panic(expected = "AUTOTILER_SEED must be a u64 seed, couldn't parse \"not a seed\"")]
+fn unparseable_seed_panics() {
+    env::set_var(SEED_ENV_VAR, "42");
+    assert_eq!(seed_from_env(), 42);
+
+    env::set_var(SEED_ENV_VAR, "not a seed");
+    seed_from_env();
+}
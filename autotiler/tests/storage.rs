@@ -0,0 +1,74 @@
+mod common;
+
+use autotiler::grid::RectVec;
+use autotiler::point::Point;
+use autotiler::random;
+use autotiler::rect::Rect;
+use autotiler::sparse::{SparseGrid, TileStorage};
+use autotiler::tile::Tile3x3;
+use autotiler::tile_grid::TileGrid;
+use common::{assert_same_tiles, with_seed};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// a 10x10 grid with exactly `filled` non-empty tiles at seeded random points
+fn grid_with(filled: usize, seed: u64) -> RectVec {
+    let mut rng = random::seeded_rng(seed);
+    let mut grid = RectVec::new(Rect::new(-3, 2, 10, 10));
+    let mut points: Vec<Point> = grid.bounds.points().collect();
+    points.shuffle(&mut rng);
+
+    for pt in &points[..filled] {
+        grid.set_pt(pt, Tile3x3::from_bits(rng.gen_range(1..512)));
+    }
+
+    grid
+}
+
+#[test]
+fn storage_is_picked_at_the_density_threshold() {
+    with_seed(|seed| {
+        // 10 of the 100 tiles is exactly the threshold, which is stored densely
+        for (filled, sparse) in [(0, true), (9, true), (10, false), (11, false), (100, false)] {
+            let grid = grid_with(filled, seed);
+            let storage = TileStorage::from_dense(grid.clone());
+
+            assert_eq!(storage.is_sparse(), sparse, "{filled} filled tiles");
+            assert_eq!(storage.density(), filled as f32 / 100.0);
+            assert_eq!(storage.bounds(), grid.bounds);
+            assert_same_tiles(&storage, &grid);
+        }
+    })
+}
+
+#[test]
+fn conversions_round_trip_across_the_threshold() {
+    with_seed(|seed| {
+        let grid = grid_with(10, seed);
+
+        let sparse = SparseGrid::from(&grid);
+        assert_eq!(sparse.len(), 10);
+        assert!(grid.iter_tiles().eq(RectVec::from(&sparse).iter_tiles()));
+
+        // a sparse grid at the threshold becomes dense, and sparse again once a tile is erased
+        let mut storage = TileStorage::Sparse(sparse).rebalanced();
+        assert!(!storage.is_sparse());
+        assert_same_tiles(&storage, &grid);
+
+        let (pt, _) = grid.iter_tiles().find(|(_, tile)| !tile.is_empty()).unwrap();
+        let mut erased = grid.clone();
+        erased.set_pt(&pt, Tile3x3::EMPTY);
+        storage.set_pt(&pt, Tile3x3::EMPTY);
+
+        let storage = storage.rebalanced();
+        assert!(storage.is_sparse());
+        assert_same_tiles(&storage, &erased);
+
+        // and dense again once it's written back
+        let mut storage = storage;
+        storage.set_pt(&pt, *grid.get_pt(&pt).unwrap());
+        let storage = storage.rebalanced();
+        assert!(!storage.is_sparse());
+        assert_same_tiles(&storage, &grid);
+    })
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use autotiler::bitboard::BitBoard;
+use autotiler::grid::{generate_test_grid_seeded, grid_strip_invalid, grid_strip_invalid_fixed_point, RectVec};
+use autotiler::matrix::{generate_random_matrix_seeded, Matrix};
+use autotiler::parallel::ParallelConfig;
+use autotiler::pixel_matrix::PixelMatrix;
+use autotiler::point::Point;
+use autotiler::rect::Rect;
+use autotiler::strip::{self, StripContext, StripStats};
+use autotiler::tile::{minimal_3x3_tile_set, Tile3x3};
+use autotiler::tile_grid::TileGrid;
+use common::{every_tile, with_seed};
+
+/// a grid at the origin, from rows of tiles
+fn grid_of(rows: &[&[Tile3x3]]) -> RectVec {
+    let mut grid = RectVec::new(Rect::new(0, 0, rows[0].len() as i32, rows.len() as i32));
+    for (y, row) in rows.iter().enumerate() {
+        for (x, tile) in row.iter().enumerate() {
+            grid.set_pt(&Point { x: x as i32, y: y as i32 }, *tile);
+        }
+    }
+    grid
+}
+
+/// the tile at `pt` after a single pass of every stripper
+fn stripped_by_every_layout(grid: &RectVec, pt: Point) -> [Option<Tile3x3>; 4] {
+    let matrix = Matrix::from(grid);
+
+    [
+        grid_strip_invalid(grid).tile_at(&pt),
+        matrix.strip_invalid().tile_at(&pt),
+        PixelMatrix::from(&matrix).strip_invalid().tile_at(&pt),
+        BitBoard::from(grid).strip_invalid().tile_at(&pt),
+    ]
+}
+
+#[test]
+fn single_pass_strips_a_corner_without_its_edge() {
+    // every neighbour around the north west corner points back at it, but the west edge is missing
+    let grid = grid_of(&[
+        &[Tile3x3::from_rows([b"...", b".#.", b"..#"]), Tile3x3::from_rows([b"...", b".#.", b"##."])],
+        &[Tile3x3::from_rows([b"..#", b".#.", b"..."]), Tile3x3::from_rows([b"##.", b".#.", b"..."])],
+    ]);
+
+    let expected = Some(Tile3x3::from_rows([b".#.", b".#.", b"..."]));
+    assert_eq!(stripped_by_every_layout(&grid, Point { x: 1, y: 1 }), [expected; 4]);
+}
+
+#[test]
+fn single_pass_clears_a_tile_without_its_centre() {
+    let full = Tile3x3::FULL;
+    let grid = grid_of(&[&[full, Tile3x3::from_rows([b"###", b"#.#", b"###"]), full]]);
+
+    assert_eq!(stripped_by_every_layout(&grid, Point { x: 1, y: 0 }), [Some(Tile3x3::EMPTY); 4]);
+}
+
+#[test]
+fn fixed_point_of_arbitrary_grid_is_valid() {
+    with_seed(|seed| {
+        let grid = generate_test_grid_seeded(&every_tile(), 40, 30, seed);
+        let (stripped, stats) = grid_strip_invalid_fixed_point(&grid);
+
+        assert!(stats.iterations > 1);
+        assert!(stats.changed > 0);
+        assert_eq!(stripped.validate(&minimal_3x3_tile_set()), vec![]);
+    })
+}
+
+#[test]
+fn fixed_point_of_arbitrary_matrix_is_valid() {
+    with_seed(|seed| {
+        let matrix = generate_random_matrix_seeded(&every_tile(), 70, 75, seed);
+        let (stripped, stats) = matrix.strip_invalid_fixed_point();
+
+        assert_eq!(stripped.validate(&minimal_3x3_tile_set()), vec![]);
+
+        // threaded passes, whatever the core count, reach the same fixed point
+        let threaded = ParallelConfig { chunk_rows: 8, serial_threshold: 0, max_threads: 4 };
+        let (threaded, threaded_stats) = strip::repeat_to_fixed_point(&matrix, |m| m.strip_invalid_with(&threaded));
+
+        assert_eq!(threaded_stats, stats);
+        assert_eq!(threaded.data, stripped.data);
+    })
+}
+
+#[test]
+fn fixed_point_of_arbitrary_bitboard_is_valid() {
+    with_seed(|seed| {
+        let board = BitBoard::from(&generate_test_grid_seeded(&every_tile(), 130, 20, seed));
+        let (stripped, _) = board.strip_invalid_fixed_point();
+
+        assert_eq!(stripped.validate(&minimal_3x3_tile_set()), vec![]);
+    })
+}
+
+#[test]
+fn grid_and_matrix_reach_the_same_fixed_point() {
+    with_seed(|seed| {
+        let grid = generate_test_grid_seeded(&every_tile(), 70, 75, seed);
+        let matrix = generate_random_matrix_seeded(&every_tile(), 70, 75, seed);
+
+        let (grid, grid_stats) = grid_strip_invalid_fixed_point(&grid);
+        let (matrix, matrix_stats) = matrix.strip_invalid_fixed_point();
+
+        assert_eq!(grid_stats, matrix_stats);
+        assert!(grid.iter_tiles().eq(matrix.iter_tiles()));
+    })
+}
+
+#[test]
+fn fixed_point_is_stable() {
+    with_seed(|seed| {
+        let grid = generate_test_grid_seeded(&every_tile(), 40, 30, seed);
+        let (stripped, _) = grid_strip_invalid_fixed_point(&grid);
+        let (_, stats) = grid_strip_invalid_fixed_point(&stripped);
+
+        assert_eq!(stats, StripStats { iterations: 1, changed: 0 });
+    })
+}
+
+#[test]
+fn tile_set_grid_is_valid_after_one_pass() {
+    with_seed(|seed| {
+        let tile_set = minimal_3x3_tile_set();
+        let grid = generate_test_grid_seeded(&tile_set, 40, 30, seed);
+        let (stripped, stats) = grid_strip_invalid_fixed_point(&grid);
+
+        assert!(stats.iterations <= 2);
+        assert_eq!(stripped.validate(&tile_set), vec![]);
+    })
+}
+
+#[test]
+fn context_strips_like_a_fresh_grid() {
+    with_seed(|seed| {
+        let grid = generate_test_grid_seeded(&every_tile(), 40, 30, seed);
+        let mut context = StripContext::default();
+
+        let mut stripped = grid.clone();
+        context.strip(&mut stripped);
+        assert!(grid_strip_invalid(&grid).iter_tiles().eq(stripped.iter_tiles()));
+
+        let (expected, stats) = grid_strip_invalid_fixed_point(&grid);
+        let mut stripped = grid.clone();
+        assert_eq!(context.strip_to_fixed_point(&mut stripped), stats);
+        assert!(expected.iter_tiles().eq(stripped.iter_tiles()));
+
+        // a stable grid takes a single pass and changes nothing
+        assert_eq!(context.strip_to_fixed_point(&mut stripped), StripStats { iterations: 1, changed: 0 });
+
+        // threaded passes reach the same fixed point
+        let mut context = StripContext::new(ParallelConfig { chunk_rows: 4, serial_threshold: 0, max_threads: 4 });
+        let mut stripped = grid.clone();
+        assert_eq!(context.strip_to_fixed_point(&mut stripped), stats);
+        assert!(expected.iter_tiles().eq(stripped.iter_tiles()));
+    })
+}
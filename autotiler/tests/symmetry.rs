@@ -0,0 +1,56 @@
+mod common;
+
+use autotiler::symmetry::{symmetry_classes, Transform};
+use autotiler::tile::minimal_3x3_tile_set;
+use common::every_tile;
+
+#[test]
+fn rotations_and_flips_undo_themselves() {
+    for tile in every_tile() {
+        assert_eq!(tile.rotate_cw().rotate_cw().rotate_cw().rotate_cw(), tile);
+        assert_eq!(tile.rotate_cw().rotate_ccw(), tile);
+        assert_eq!(tile.rotate_ccw().rotate_cw(), tile);
+        assert_eq!(tile.flip_h().flip_h(), tile);
+        assert_eq!(tile.flip_v().flip_v(), tile);
+        assert_eq!(tile.transpose().transpose(), tile);
+
+        for transform in Transform::ALL {
+            assert_eq!(transform.inverse().apply(transform.apply(tile)), tile, "{transform:?}");
+        }
+    }
+}
+
+#[test]
+fn transforms_keep_tiles_in_the_tile_set() {
+    let tile_set = minimal_3x3_tile_set();
+
+    for tile in &tile_set {
+        for transform in Transform::ALL {
+            assert!(tile_set.contains(&transform.apply(*tile)), "{transform:?} of {tile:?}");
+        }
+    }
+}
+
+#[test]
+fn symmetry_classes_cover_the_tile_set_once() {
+    let tile_set = minimal_3x3_tile_set();
+    let classes = symmetry_classes(&tile_set);
+
+    let mut covered: Vec<usize> = classes.iter()
+        .flat_map(|class| class.members.iter().map(|(idx, _)| *idx))
+        .collect();
+    covered.sort();
+    assert_eq!(covered, (0..48).collect::<Vec<_>>());
+
+    for class in &classes {
+        assert!(class.members.contains(&(class.canonical, Transform::Identity)));
+
+        for (idx, transform) in &class.members {
+            assert_eq!(transform.apply(tile_set[*idx]), tile_set[class.canonical]);
+        }
+
+        for (idx, tile) in class.derive(tile_set[class.canonical]) {
+            assert_eq!(tile, tile_set[idx]);
+        }
+    }
+}
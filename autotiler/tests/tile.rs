@@ -0,0 +1,100 @@
+mod common;
+
+use autotiler::tile::{Tile3x3, C_IDX, E_IDX, N_IDX, NEIGHBOUR_IDXS, NE_IDX, NW_IDX, SE_IDX, SW_IDX, S_IDX, W_IDX};
+use common::every_tile;
+
+#[test]
+fn const_constructors_agree() {
+    const ROWS: Tile3x3 = Tile3x3::from_rows([b"#..", b".##", b"..#"]);
+    const ARRAY: Tile3x3 = Tile3x3::from_array([true, false, false, false, true, true, false, false, true]);
+    const NEIGHBOURS: Tile3x3 = Tile3x3::from_neighbours(true, 0b1001_0001);
+
+    let bits = 1 << NW_IDX | 1 << C_IDX | 1 << E_IDX | 1 << SE_IDX;
+    assert_eq!(ROWS.bits(), bits);
+    assert_eq!(ARRAY, ROWS);
+    assert_eq!(NEIGHBOURS, ROWS);
+
+    assert_eq!(Tile3x3::from_rows([b"...", b"...", b"..."]), Tile3x3::EMPTY);
+    assert_eq!(Tile3x3::from_rows([b"###", b"###", b"###"]), Tile3x3::FULL);
+}
+
+#[test]
+fn bits_above_the_ninth_are_always_clear() {
+    assert_eq!(Tile3x3::from_bits(u16::MAX), Tile3x3::FULL);
+    assert_eq!(Tile3x3::from_bits(1 << 9), Tile3x3::EMPTY);
+
+    for tile in every_tile() {
+        assert_eq!((!tile).bits() & !Tile3x3::MASK, 0);
+        assert_eq!(!!tile, tile);
+        assert_eq!(Tile3x3::from_array(tile.to_array()), tile);
+        assert_eq!(<[bool; 9]>::from(tile), tile.to_array());
+    }
+}
+
+#[test]
+fn bitwise_operators_act_on_the_bits() {
+    for (a, b) in every_tile().into_iter().flat_map(|a| every_tile().into_iter().map(move |b| (a, b))) {
+        assert_eq!((a & b).bits(), a.bits() & b.bits());
+        assert_eq!((a | b).bits(), a.bits() | b.bits());
+        assert_eq!((a ^ b).bits(), a.bits() ^ b.bits());
+        assert_eq!((!a).bits(), !a.bits() & Tile3x3::MASK);
+
+        let (mut and, mut or, mut xor) = (a, a, a);
+        and &= b;
+        or |= b;
+        xor ^= b;
+        assert_eq!((and, or, xor), (a & b, a | b, a ^ b));
+    }
+}
+
+#[test]
+fn neighbour_helpers_round_trip() {
+    for tile in every_tile() {
+        let neighbours = tile.neighbours();
+
+        assert_eq!(Tile3x3::from_neighbours(tile.get(C_IDX), neighbours), tile);
+        for (i, idx) in NEIGHBOUR_IDXS.iter().enumerate() {
+            assert_eq!(neighbours >> i & 1 == 1, tile.get(*idx));
+        }
+
+        assert_eq!(tile.edges() | tile.corners() | Tile3x3::from_bits(tile.bits() & Tile3x3::CENTRE_MASK), tile);
+        assert!((tile.edges() & tile.corners()).is_empty());
+    }
+
+    let full = Tile3x3::FULL;
+    assert_eq!(full.edges().bits(), 1 << N_IDX | 1 << W_IDX | 1 << E_IDX | 1 << S_IDX);
+    assert_eq!(full.corners().bits(), 1 << NW_IDX | 1 << NE_IDX | 1 << SW_IDX | 1 << SE_IDX);
+    assert_eq!(full.neighbours(), 0xff);
+    assert_eq!(Tile3x3::from_neighbours(false, 0xff), full.with(C_IDX, false));
+}
+
+#[test]
+fn the_ninth_bit_is_the_last_one() {
+    assert!(Tile3x3::FULL.get(8));
+    assert_eq!(Tile3x3::EMPTY.with(8, true).bits(), 1 << 8);
+}
+
+#[test]
+#[should_panic(expected = "tile bit index out of range")]
+fn setting_bit_9_panics() {
+    let mut tile = Tile3x3::EMPTY;
+    tile.set(9, true);
+}
+
+#[test]
+#[should_panic(expected = "tile bit index out of range")]
+fn reading_bit_9_panics() {
+    Tile3x3::FULL.get(9);
+}
+
+#[test]
+#[should_panic(expected = "tile bit index out of range")]
+fn setting_bit_16_panics() {
+    let _ = Tile3x3::EMPTY.with(16, true);
+}
+
+#[test]
+#[should_panic(expected = "tile bit index out of range")]
+fn reading_bit_16_panics() {
+    Tile3x3::FULL.get(16);
+}
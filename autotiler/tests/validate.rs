@@ -0,0 +1,71 @@
+use autotiler::point::Point;
+use autotiler::tile::{minimal_3x3_tile_set, Tile3x3, E_IDX, NW_IDX};
+use autotiler::validate::{validate, Issue, Rule};
+
+const CENTRE: Tile3x3 = Tile3x3::from_rows([b"...", b".#.", b"..."]);
+
+#[test]
+fn edge_without_a_reciprocal_edge() {
+    let grid = vec![vec![Tile3x3::from_rows([b"...", b".##", b"..."]), CENTRE]];
+
+    assert_eq!(validate(&grid, &minimal_3x3_tile_set()), vec![Issue {
+        pt: Point { x: 0, y: 0 },
+        bit: Some(E_IDX),
+        neighbour: Some(Point { x: 1, y: 0 }),
+        rule: Rule::UnreciprocatedEdge,
+    }]);
+}
+
+#[test]
+fn corner_without_reciprocal_corners() {
+    let grid = vec![
+        vec![CENTRE, Tile3x3::from_rows([b"...", b".#.", b".#."])],
+        vec![Tile3x3::from_rows([b"...", b".##", b"..."]), Tile3x3::from_rows([b"##.", b"##.", b"..."])],
+    ];
+
+    // all three neighbours around the corner are named, in the order they're checked
+    let issue = |x, y| Issue {
+        pt: Point { x: 1, y: 1 },
+        bit: Some(NW_IDX),
+        neighbour: Some(Point { x, y }),
+        rule: Rule::UnreciprocatedEdge,
+    };
+    assert_eq!(validate(&grid, &minimal_3x3_tile_set()), vec![issue(0, 0), issue(1, 0), issue(0, 1)]);
+}
+
+#[test]
+fn corner_without_its_edges() {
+    let grid = vec![vec![Tile3x3::from_rows([b"#..", b".#.", b"..."])]];
+
+    assert_eq!(validate(&grid, &[]), vec![Issue {
+        pt: Point { x: 0, y: 0 },
+        bit: Some(NW_IDX),
+        neighbour: None,
+        rule: Rule::DiagonalWithoutOrthogonals,
+    }]);
+}
+
+#[test]
+fn bit_with_the_centre_clear() {
+    let grid = vec![vec![CENTRE, Tile3x3::from_rows([b"...", b"..#", b"..."])]];
+
+    assert_eq!(validate(&grid, &[]), vec![Issue {
+        pt: Point { x: 1, y: 0 },
+        bit: Some(E_IDX),
+        neighbour: None,
+        rule: Rule::BitWithCentreClear,
+    }]);
+}
+
+#[test]
+fn tile_missing_from_the_tile_set() {
+    let grid = vec![vec![Tile3x3::EMPTY, CENTRE]];
+
+    assert_eq!(validate(&grid, &[Tile3x3::EMPTY]), vec![Issue {
+        pt: Point { x: 1, y: 0 },
+        bit: None,
+        neighbour: None,
+        rule: Rule::NotInTileSet,
+    }]);
+    assert_eq!(validate(&grid, &minimal_3x3_tile_set()), vec![]);
+}
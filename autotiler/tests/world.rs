@@ -0,0 +1,111 @@
+mod common;
+
+use autotiler::grid::{grid_strip_invalid, grid_strip_invalid_fixed_point, RectVec};
+use autotiler::point::Point;
+use autotiler::random;
+use autotiler::rect::Rect;
+use autotiler::tile::Tile3x3;
+use autotiler::tile_grid::TileGrid;
+use autotiler::world::ChunkedWorld;
+use common::{assert_same_tiles, every_tile, seeded_grid, with_seed};
+use rand::Rng;
+
+/// arbitrary tiles inside of an empty border, so the grid has no neighbours outside of it,
+/// just like the unbounded world
+fn bordered_grid(bounds: Rect, seed: u64) -> RectVec {
+    let mut grid = seeded_grid(bounds.clone(), &every_tile(), seed);
+
+    for pt in bounds.points() {
+        if pt.x == bounds.x || pt.y == bounds.y || pt.x == bounds.x + bounds.w - 1 || pt.y == bounds.y + bounds.h - 1 {
+            grid.set_pt(&pt, Tile3x3::EMPTY);
+        }
+    }
+
+    grid
+}
+
+fn world_from(grid: &RectVec, chunk_size: i32) -> ChunkedWorld {
+    let mut world = ChunkedWorld::new(chunk_size);
+    for (pt, tile) in grid.iter_tiles() {
+        world.set_pt(&pt, tile);
+    }
+    world
+}
+
+#[test]
+fn reads_and_writes_cross_chunk_seams() {
+    let mut world = ChunkedWorld::new(4);
+    let points = [
+        Point { x: -1, y: -1 },
+        Point { x: 0, y: -1 },
+        Point { x: 3, y: 0 },
+        Point { x: 4, y: 0 },
+        Point { x: -5, y: 7 },
+    ];
+
+    for (i, pt) in points.iter().enumerate() {
+        world.set_pt(pt, Tile3x3::from_bits(1 << 4 | i as u16));
+    }
+
+    for (i, pt) in points.iter().enumerate() {
+        assert_eq!(world.get_pt(pt), Some(&Tile3x3::from_bits(1 << 4 | i as u16)));
+        assert!(world.chunk_bounds(&world.chunk_coord(pt)).contains(pt));
+    }
+
+    // no two of the points share a chunk
+    assert_eq!(world.chunks().count(), points.len());
+    assert_eq!(world.chunk_coord(&Point { x: -1, y: -1 }), Point { x: -1, y: -1 });
+    assert_eq!(world.chunk_coord(&Point { x: 4, y: 0 }), Point { x: 1, y: 0 });
+    assert_eq!(world.bounds(), Rect::new(-8, -4, 16, 12));
+
+    // unallocated chunks read as empty without allocating
+    assert_eq!(world.get_pt(&Point { x: 100, y: 100 }), None);
+    assert_eq!(world.tile_at(&Point { x: 100, y: 100 }), Some(Tile3x3::EMPTY));
+    world.set_pt(&Point { x: 100, y: 100 }, Tile3x3::EMPTY);
+    assert_eq!(world.chunks().count(), points.len());
+}
+
+#[test]
+fn stripping_across_seams_matches_a_single_grid() {
+    with_seed(|seed| {
+        let grid = bordered_grid(Rect::new(-7, -6, 23, 17), seed);
+
+        for chunk_size in [1, 3, 4, 64] {
+            let world = world_from(&grid, chunk_size);
+            assert_same_tiles(&world, &grid);
+
+            assert_same_tiles(&world.strip_invalid(), &grid_strip_invalid(&grid));
+
+            let (expected, expected_stats) = grid_strip_invalid_fixed_point(&grid);
+            let (stripped, stats) = world.strip_invalid_fixed_point();
+            assert_same_tiles(&stripped, &expected);
+            assert_eq!(stats.iterations, expected_stats.iterations);
+        }
+    })
+}
+
+#[test]
+fn painting_across_seams_matches_a_single_grid() {
+    with_seed(|seed| {
+        let mut rng = random::seeded_rng(seed);
+        let mut grid = RectVec::new(Rect::new(-9, -9, 20, 20));
+        let mut world = ChunkedWorld::new(4);
+
+        for _ in 0..40 {
+            // stay off the border, so the grid never clips what the world paints
+            let mut point = || Point { x: rng.gen_range(-8..10), y: rng.gen_range(-8..10) };
+            let (from, to, pt) = (point(), point(), point());
+
+            grid.paint_path(from, to);
+            world.paint_path(from, to);
+            grid.paint_fill(pt);
+            world.paint_fill(pt);
+            assert_same_tiles(&world, &grid);
+
+            let pt = point();
+            grid.erase(pt);
+            world.erase(pt);
+            assert_same_tiles(&world, &grid);
+        }
+    })
+}
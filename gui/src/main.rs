@@ -6,6 +6,7 @@ use gtk::Orientation::{Horizontal, Vertical};
 use image::{ImageBuffer, Rgba};
 use autotiler::matrix::{Matrix, MatrixTile};
 use autotiler::tile::Tile3x3;
+use autotiler::tile_grid::TileGrid;
 
 fn main() {
     // Initialize GTK.